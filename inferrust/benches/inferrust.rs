@@ -8,9 +8,9 @@ pub fn simpsons_total(c: &mut Criterion) {
         b.iter(|| {
             let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(SIMPSONS));
             assert_eq!(graph.size(), 11);
-            let mut rules = <Vec<Box<Rule>> as RuleSet>::new();
+            let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
             rules.fire_all(&mut graph);
-            assert_eq!(graph.size(), 23);
+            assert_eq!(graph.size(), 30);
         })
     });
 }
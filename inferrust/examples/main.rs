@@ -37,7 +37,7 @@ fn main() {
     let mut nt_stringifier = NtSerializer::new_stringifier();
     let example2 = nt_stringifier.serialize_graph(&mut graph).unwrap().as_str();
     println!("The resulting graph\n{}", example2);
-    let mut rules = <Vec<Box<Rule>> as RuleSet>::new();
+    let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
     // rules.specialize(std::rc::Rc::new(&graph));
     rules.fire_all(&mut graph);
     println!("{} triples", graph.size());
@@ -1,9 +1,10 @@
 #![allow(non_snake_case)]
 
 use sophia::ns::*;
-use sophia::term::factory::{RcTermFactory, TermFactory};
-use sophia::term::{RcTerm, Term, TermData};
+use sophia::term::factory::{ArcTermFactory, TermFactory};
+use sophia::term::{ArcTerm, Term, TermData};
 
+use std::collections::HashMap;
 use std::convert::TryInto;
 
 use bimap::hash::BiHashMap;
@@ -13,11 +14,22 @@ use super::TripleStore;
 pub struct NodeDictionary {
     res_ctr: u64,
     prop_ctr: u32,
-    removed_val: Vec<u64>,
-    resources: BiHashMap<RcTerm, u64>,
-    properties: BiHashMap<RcTerm, u32>,
+    /// The smallest property index of the vocabulary, the user properties come below
+    vocab_prop_ctr: u32,
+    /// The greatest resource index of the vocabulary, the user resources come above
+    vocab_res_ctr: u64,
+    removed_val: Vec<(u64, u32)>,
+    resources: BiHashMap<ArcTerm, u64>,
+    properties: BiHashMap<ArcTerm, u32>,
+    same_as: HashMap<u64, u64>,
+    equivalents: HashMap<u64, Vec<u64>>,
     pub ts: TripleStore,
-    factory: RcTermFactory,
+    /// The asserted triples of `ts`, as opposed to the inferred ones
+    pub asserted: TripleStore,
+    /// The asserted triples with the indexes of their own terms,
+    /// once some `owl:sameAs` cliques have been merged
+    pub(crate) unmerged: Option<TripleStore>,
+    factory: ArcTermFactory,
     pub rdfsResource: u64,
     pub rdfsClass: u64,
     pub rdfsDatatype: u64,
@@ -62,6 +74,7 @@ pub struct NodeDictionary {
     pub owldifferentFrom: u32,
     pub owlallDifferent: u32,
     pub owlallDisjointClasses: u32,
+    pub owlallDisjointProperties: u32,
     pub owlallValuesFrom: u32,
     pub owlannotationProperty: u32,
     pub owlassertionProperty: u32,
@@ -74,6 +87,7 @@ pub struct NodeDictionary {
     pub unionOf: u32,
     pub owlinverseFunctionalProperty: u32,
     pub irreflexiveProperty: u32,
+    pub asymmetricProperty: u32,
     pub maxCardinality: u32,
     pub members: u32,
     pub nothing: u32,
@@ -87,6 +101,7 @@ pub struct NodeDictionary {
     pub owltargetIndividual: u32,
     pub targetValue: u32,
     pub maxQualifiedCardinality: u32,
+    pub owlhasValue: u32,
 }
 
 impl NodeDictionary {
@@ -96,11 +111,17 @@ impl NodeDictionary {
         let mut me = Self {
             res_ctr: Self::START_INDEX as u64,
             prop_ctr: Self::START_INDEX,
+            vocab_prop_ctr: Self::START_INDEX,
+            vocab_res_ctr: Self::START_INDEX as u64,
             removed_val: vec![],
-            resources: BiHashMap::<RcTerm, u64>::new(),
-            properties: BiHashMap::<RcTerm, u32>::new(),
+            resources: BiHashMap::<ArcTerm, u64>::new(),
+            properties: BiHashMap::<ArcTerm, u32>::new(),
+            same_as: HashMap::new(),
+            equivalents: HashMap::new(),
             ts,
-            factory: RcTermFactory::new(),
+            asserted: TripleStore::new(),
+            unmerged: None,
+            factory: ArcTermFactory::new(),
             rdfsResource: 0,
             rdfsClass: 0,
             rdfsDatatype: 0,
@@ -145,6 +166,7 @@ impl NodeDictionary {
             owldifferentFrom: 0,
             owlallDifferent: 0,
             owlallDisjointClasses: 0,
+            owlallDisjointProperties: 0,
             owlallValuesFrom: 0,
             owlannotationProperty: 0,
             owlassertionProperty: 0,
@@ -157,6 +179,7 @@ impl NodeDictionary {
             unionOf: 0,
             owlinverseFunctionalProperty: 0,
             irreflexiveProperty: 0,
+            asymmetricProperty: 0,
             maxCardinality: 0,
             members: 0,
             nothing: 0,
@@ -170,18 +193,22 @@ impl NodeDictionary {
             owltargetIndividual: 0,
             targetValue: 0,
             maxQualifiedCardinality: 0,
+            owlhasValue: 0,
         };
         me.init_const();
+        me.vocab_prop_ctr = me.prop_ctr;
+        me.vocab_res_ctr = me.res_ctr;
         me
     }
 
     pub fn add<TD: TermData>(&mut self, term: &Term<TD>) -> u64 {
         let t = self.factory.copy(term);
         if self.properties.contains_left(&t) {
-            return *self.properties.get_by_left(&t).expect("Err") as u64;
+            let index = *self.properties.get_by_left(&t).expect("Err") as u64;
+            return self.canonical(index);
         }
         if self.resources.contains_left(&t) {
-            *self.resources.get_by_left(&t).expect("Err")
+            self.canonical(*self.resources.get_by_left(&t).expect("Err"))
         } else {
             self.res_ctr += 1;
             self.resources.insert(t, self.res_ctr);
@@ -194,7 +221,8 @@ impl NodeDictionary {
         if self.resources.contains_left(&t) {
             self.remap_res_to_prop(t)
         } else if self.properties.contains_left(&t) {
-            *self.properties.get_by_left(&t).expect("Err")
+            let index = *self.properties.get_by_left(&t).expect("Err") as u64;
+            self.canonical(index) as u32
         } else {
             self.prop_ctr -= 1;
             self.properties.insert(t, self.prop_ctr);
@@ -202,17 +230,27 @@ impl NodeDictionary {
         }
     }
 
-    fn remap_res_to_prop(&mut self, t: RcTerm) -> u32 {
+    fn remap_res_to_prop(&mut self, t: ArcTerm) -> u32 {
         let old = self.resources.remove_by_left(&t).expect("Err").1;
         self.prop_ctr -= 1;
         let p = self.prop_ctr;
         self.properties.insert(t, p);
-        self.removed_val.push(old);
+        self.removed_val.push((old, p));
         self.ts.res_to_prop(old, p);
+        self.asserted.res_to_prop(old, p);
+        if let Some(unmerged) = &mut self.unmerged {
+            unmerged.res_to_prop(old, p);
+        }
         p
     }
 
-    pub fn get_term(&self, index: u64) -> &RcTerm {
+    /// Return the resources which have been turned into properties, with their new index,
+    /// in the order in which they have been remapped
+    pub fn remapped(&self) -> &[(u64, u32)] {
+        &self.removed_val
+    }
+
+    pub fn get_term(&self, index: u64) -> &ArcTerm {
         if index < Self::START_INDEX as u64 {
             self.properties
                 .get_by_right(&(index as u32))
@@ -228,7 +266,15 @@ impl NodeDictionary {
     where
         T: TermData,
     {
-        let inner_term = RcTerm::from(t);
+        self.get_raw_index(t).map(|index| self.canonical(index))
+    }
+
+    /// Return the index of the term `t` itself, rather than the canonical index of its clique
+    pub(crate) fn get_raw_index<T>(&self, t: &Term<T>) -> Option<u64>
+    where
+        T: TermData,
+    {
+        let inner_term = ArcTerm::from(t);
         if self.properties.contains_left(&inner_term) {
             Some(*self.properties.get_by_left(&inner_term).unwrap() as u64)
         } else if self.resources.contains_left(&inner_term) {
@@ -238,6 +284,342 @@ impl NodeDictionary {
         }
     }
 
+    /// Return the value of the term of index `index`,
+    /// if it is a literal of datatype `xsd:nonNegativeInteger`
+    pub fn non_negative_integer(&self, index: u64) -> Option<u64> {
+        if Self::is_property(index) {
+            return None;
+        }
+        let term = self.get_term(index);
+        let value = term.value();
+        let datatype = self.get_term(self.xsdnonNegativeInteger).clone();
+        if *term != ArcTerm::new_literal_dt(&*value, datatype).ok()? {
+            return None;
+        }
+        value.trim().parse().ok()
+    }
+
+    /// Return `true` if the term of index `index` is a literal
+    pub fn is_literal(&self, index: u64) -> bool {
+        matches!(self.get_term(index), Term::Literal(..))
+    }
+
+    /// Return `true` if the terms of indexes `i1` and `i2` are literals known to have distinct values
+    ///
+    /// Only the values of the literals of datatype `xsd:string`, `xsd:boolean`,
+    /// `xsd:decimal` or an integer type are known, so that e.g. `"1"^^xsd:integer`
+    /// and `"01"^^xsd:integer` are not distinct, while the literals of any other datatype
+    /// (language-tagged strings, floating-point numbers, dates...) never are.
+    pub fn distinct_literals(&self, i1: u64, i2: u64) -> bool {
+        match (self.literal_value(i1), self.literal_value(i2)) {
+            (Some(v1), Some(v2)) => v1 != v2,
+            _ => false,
+        }
+    }
+
+    /// Return the value of the term of index `index`,
+    /// if it is a literal whose value is known (see [`distinct_literals`](#method.distinct_literals))
+    fn literal_value(&self, index: u64) -> Option<LiteralValue> {
+        if Self::is_property(index) || !self.is_literal(index) {
+            return None;
+        }
+        let term = self.get_term(index);
+        let value = term.value();
+        let has_datatype = |name: &str| {
+            let datatype = ArcTerm::new_iri(format!("{}{}", XSD_PREFIX, name)).ok();
+            let literal = datatype.and_then(|dt| ArcTerm::new_literal_dt(&*value, dt).ok());
+            literal.as_ref() == Some(term)
+        };
+        if has_datatype("string") {
+            return Some(LiteralValue::String(value));
+        }
+        if has_datatype("boolean") {
+            return match value.trim() {
+                "true" | "1" => Some(LiteralValue::Boolean(true)),
+                "false" | "0" => Some(LiteralValue::Boolean(false)),
+                _ => None,
+            };
+        }
+        if DECIMAL_DATATYPES.iter().any(|name| has_datatype(name)) {
+            return canonical_decimal(value.trim()).map(LiteralValue::Decimal);
+        }
+        None
+    }
+
+    /// Return `true` if the term of index `index` is a container membership property `rdf:_n`
+    pub fn is_container_membership_property(&self, index: u64) -> bool {
+        if !Self::is_property(index) {
+            return false;
+        }
+        let term = self.get_term(index);
+        if let Term::Iri(_) = term {
+            let value = term.value();
+            match value.strip_prefix(RDF_MEMBER_PREFIX) {
+                Some(n) => {
+                    !n.is_empty() && !n.starts_with('0') && n.bytes().all(|b| b.is_ascii_digit())
+                }
+                None => false,
+            }
+        } else {
+            false
+        }
+    }
+
+    /// Return the RDF and RDFS axiomatic triples
+    ///
+    /// As there are infinitely many container membership properties `rdf:_n`,
+    /// only the axiomatic triples of those which are properties of this dictionary are returned.
+    /// The triple `xsd:string rdf:type rdfs:Datatype` is added,
+    /// as `xsd:string` is a recognized datatype (rule rdfs1).
+    pub fn axiomatic_triples(&self) -> Vec<[u64; 3]> {
+        let rdftype = self.rdftype as u64;
+        let domain = self.rdfsdomain as u64;
+        let range = self.rdfsrange as u64;
+        let sub_class_of = self.rdfssubClassOf as u64;
+        let property = self.rdfProperty as u64;
+        let resource = self.rdfsResource;
+        let class = self.rdfsClass;
+        let literal = self.rdfsLiteral;
+        let mut triples = Vec::new();
+        // RDF axiomatic triples
+        for &p in &[
+            self.rdftype,
+            self.rdfsubject,
+            self.rdfpredicate,
+            self.rdfobject,
+            self.rdffirst,
+            self.rdfrest,
+            self.rdfValue,
+        ] {
+            triples.push([p as u64, rdftype, property]);
+        }
+        triples.push([self.rdfnil, rdftype, self.rdfList]);
+        // RDFS axiomatic triples
+        for &(p, d, r) in &[
+            (self.rdftype, resource, class),
+            (self.rdfsdomain, property, class),
+            (self.rdfsrange, property, class),
+            (self.rdfssubPropertyOf, property, property),
+            (self.rdfssubClassOf, class, class),
+            (self.rdfsubject, self.rdfStatement, resource),
+            (self.rdfpredicate, self.rdfStatement, resource),
+            (self.rdfobject, self.rdfStatement, resource),
+            (self.rdfsMember, resource, resource),
+            (self.rdffirst, self.rdfList, resource),
+            (self.rdfrest, self.rdfList, self.rdfList),
+            (self.rdfsSeeAlso, resource, resource),
+            (self.rdfsisDefinedBy, resource, resource),
+            (self.rdfsComment, resource, literal),
+            (self.rdfsLabel, resource, literal),
+            (self.rdfValue, resource, resource),
+        ] {
+            triples.push([p as u64, domain, d]);
+            triples.push([p as u64, range, r]);
+        }
+        for &c in &[self.rdfAlt, self.rdfBag, self.rdfSeq] {
+            triples.push([c, sub_class_of, self.rdfsContainer]);
+        }
+        triples.push([
+            self.rdfsContainerMembershipProperty as u64,
+            sub_class_of,
+            property,
+        ]);
+        triples.push([
+            self.rdfsisDefinedBy as u64,
+            self.rdfssubPropertyOf as u64,
+            self.rdfsSeeAlso as u64,
+        ]);
+        triples.push([self.rdfsDatatype, sub_class_of, class]);
+        triples.push([self.xsdstring, rdftype, self.rdfsDatatype]);
+        // container membership properties
+        for (_, &p) in self.properties.iter() {
+            let p = p as u64;
+            if self.is_container_membership_property(p) {
+                triples.push([p, rdftype, property]);
+                triples.push([p, rdftype, self.rdfsContainerMembershipProperty as u64]);
+                triples.push([p, domain, resource]);
+                triples.push([p, range, resource]);
+            }
+        }
+        triples
+            .into_iter()
+            .map(|[s, p, o]| [self.canonical(s), p, self.canonical(o)])
+            .collect()
+    }
+
+    /// Return the canonical index of the `owl:sameAs` equivalence class of `index`
+    pub fn canonical(&self, index: u64) -> u64 {
+        *self.same_as.get(&index).unwrap_or(&index)
+    }
+
+    /// Return `true` if `index` is the index of a term of the RDF, RDFS or OWL vocabulary
+    pub fn is_vocabulary(&self, index: u64) -> bool {
+        if Self::is_property(index) {
+            index >= self.vocab_prop_ctr as u64
+        } else {
+            index <= self.vocab_res_ctr
+        }
+    }
+
+    /// Return the priority of `index` for being the canonical index of its `owl:sameAs` clique,
+    /// the smallest one winning: properties come first (so that the canonical index
+    /// of a property remains a property), then the terms of the vocabulary
+    /// (so that a user term never replaces e.g. `rdf:type`), then the oldest terms.
+    fn rank(&self, index: u64) -> (bool, bool, u64) {
+        let property = Self::is_property(index);
+        let key = if property {
+            Self::START_INDEX as u64 - index
+        } else {
+            index
+        };
+        (!property, !self.is_vocabulary(index), key)
+    }
+
+    /// Return the indexes of all the members of the `owl:sameAs` equivalence class of `index`
+    ///
+    /// The first one is the canonical index, the only one used in the store.
+    pub fn equivalents(&self, index: u64) -> Vec<u64> {
+        let canonical = self.canonical(index);
+        match self.equivalents.get(&canonical) {
+            Some(members) => members.clone(),
+            None => vec![canonical],
+        }
+    }
+
+    /// Decode the RDF list of the store starting at node `head`
+    ///
+    /// Return `None` if the list is malformed (see [`TripleStore::list`](struct.TripleStore.html#method.list)).
+    pub fn list(&self, head: u64) -> Option<Vec<u64>> {
+        self.ts.list(
+            head,
+            Self::prop_idx_to_idx(self.rdffirst as u64),
+            Self::prop_idx_to_idx(self.rdfrest as u64),
+            self.canonical(self.rdfnil),
+        )
+    }
+
+    /// Merge every `owl:sameAs` clique of the store into its canonical index
+    ///
+    /// This implements EQ-SYM, EQ-TRANS and EQ-REP-S/P/O without materializing
+    /// the triples of each member: the canonical index (a vocabulary term if there is one,
+    /// see [`is_vocabulary`](#method.is_vocabulary)) replaces the others everywhere in the store,
+    /// and the members of each clique are remembered by the dictionary
+    /// (see [`equivalents`](#method.equivalents)).
+    ///
+    /// Return `true` if the store has been rewritten, in which case it must be sorted again
+    /// (the asserted triples are sorted again by this method).
+    pub fn merge_same_as(&mut self) -> bool {
+        let idx = Self::prop_idx_to_idx(self.owlsameAs as u64);
+        let mut parent: HashMap<u64, u64> = HashMap::new();
+        if let Some(chunk) = self.ts.elem.get(idx) {
+            for pair in &chunk[0] {
+                let a = find(&mut parent, pair[0]);
+                let b = find(&mut parent, pair[1]);
+                if a != b {
+                    if self.rank(a) < self.rank(b) {
+                        parent.insert(b, a);
+                    } else {
+                        parent.insert(a, b);
+                    }
+                }
+            }
+        }
+        if parent.is_empty() {
+            return false;
+        }
+        let merged: Vec<u64> = parent.keys().cloned().collect();
+        let mut map = HashMap::with_capacity(merged.len());
+        for member in merged {
+            let canonical = find(&mut parent, member);
+            map.insert(member, canonical);
+        }
+        for (&member, &canonical) in &map {
+            let mut class = self
+                .equivalents
+                .remove(&member)
+                .unwrap_or_else(|| vec![member]);
+            for &m in &class {
+                self.same_as.insert(m, canonical);
+            }
+            let members = self
+                .equivalents
+                .entry(canonical)
+                .or_insert_with(|| vec![canonical]);
+            members.append(&mut class);
+            members.sort_unstable();
+            members.dedup();
+            let position = members.iter().position(|&m| m == canonical).expect("Err");
+            members[..=position].rotate_right(1);
+        }
+        if self.unmerged.is_none() {
+            self.unmerged = Some(self.asserted.clone());
+        }
+        self.ts.replace_all(&map);
+        self.asserted.replace_all(&map);
+        self.asserted.sort();
+        true
+    }
+
+    /// Undo the merges of the `owl:sameAs` cliques, the store being reset to the asserted triples
+    ///
+    /// Return `false` if no clique has been merged, in which case nothing is done.
+    /// Otherwise, the store must be saturated again.
+    pub(crate) fn unmerge_same_as(&mut self) -> bool {
+        match self.unmerged.take() {
+            Some(unmerged) => {
+                self.same_as.clear();
+                self.equivalents.clear();
+                self.ts = unmerged.clone();
+                self.asserted = unmerged;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add the sorted triples of `added` to the asserted ones
+    ///
+    /// They must use the indexes of their own terms (see [`get_raw_index`](#method.get_raw_index)),
+    /// and are stored with the canonical ones in `asserted`.
+    /// Return the triples of `added` with the canonical indexes, sorted.
+    pub(crate) fn add_asserted(&mut self, added: &TripleStore) -> TripleStore {
+        let mut canonical = added.clone();
+        if let Some(unmerged) = &mut self.unmerged {
+            unmerged.merge(added);
+            canonical.replace_all(&self.same_as);
+            canonical.sort();
+        }
+        self.asserted.merge(&canonical);
+        canonical
+    }
+
+    /// Remove the sorted triples of `removed` from the asserted ones,
+    /// and return those which were asserted, with the canonical indexes, sorted
+    ///
+    /// They must use the indexes of their own terms (see [`get_raw_index`](#method.get_raw_index)):
+    /// a triple using the canonical indexes remains asserted
+    /// as long as the same triple is asserted for other members of the cliques.
+    pub(crate) fn remove_asserted(&mut self, removed: &TripleStore) -> TripleStore {
+        match &mut self.unmerged {
+            Some(unmerged) => {
+                let mut removed = removed.intersection(unmerged);
+                *unmerged = unmerged.difference(&removed);
+                let mut asserted = unmerged.clone();
+                asserted.replace_all(&self.same_as);
+                asserted.sort();
+                self.asserted = asserted;
+                removed.replace_all(&self.same_as);
+                removed.sort();
+                removed
+            }
+            None => {
+                let removed = removed.intersection(&self.asserted);
+                self.asserted = self.asserted.difference(&removed);
+                removed
+            }
+        }
+    }
+
     pub fn prop_idx_to_idx(prop_idx: u64) -> usize {
         (/*dbg!(*/Self::START_INDEX as u64 - prop_idx - 1/*)*/)
             .try_into()
@@ -248,6 +630,10 @@ impl NodeDictionary {
         Self::START_INDEX as u64 - idx as u64 - 1
     }
 
+    pub fn is_property(index: u64) -> bool {
+        index < Self::START_INDEX as u64
+    }
+
     fn init_const(&mut self) {
         // ---------------RDFS
         self.rdfsResource = self.add(&rdfs::Resource);
@@ -332,5 +718,147 @@ impl NodeDictionary {
         self.owltargetIndividual = self.add_property(&owl::targetIndividual);
         self.targetValue = self.add_property(&owl::targetValue);
         self.maxQualifiedCardinality = self.add_property(&owl::maxQualifiedCardinality);
+        self.owlhasValue = self.add_property(&owl::hasValue);
+        self.owlallDisjointProperties = self.add_property(&owl::AllDisjointProperties);
+        self.asymmetricProperty = self.add_property(&owl::AsymmetricProperty);
+    }
+}
+
+/// The prefix of the container membership properties `rdf:_n`
+const RDF_MEMBER_PREFIX: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#_";
+
+/// The namespace of the XML Schema datatypes
+const XSD_PREFIX: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// The XML Schema datatypes whose values are decimal numbers
+const DECIMAL_DATATYPES: [&str; 14] = [
+    "decimal",
+    "integer",
+    "nonNegativeInteger",
+    "positiveInteger",
+    "nonPositiveInteger",
+    "negativeInteger",
+    "long",
+    "int",
+    "short",
+    "byte",
+    "unsignedLong",
+    "unsignedInt",
+    "unsignedShort",
+    "unsignedByte",
+];
+
+/// The value of a literal, as compared by
+/// [`NodeDictionary::distinct_literals`](struct.NodeDictionary.html#method.distinct_literals)
+#[derive(PartialEq)]
+enum LiteralValue {
+    String(String),
+    Boolean(bool),
+    /// A decimal number, in its canonical lexical form
+    Decimal(String),
+}
+
+/// Return the canonical form of the decimal number `lexical`, if it is a valid one
+///
+/// The sign `+`, the leading zeros and the trailing zeros of the fractional part are removed.
+fn canonical_decimal(lexical: &str) -> Option<String> {
+    let (negative, digits) = match lexical.as_bytes().first() {
+        Some(b'-') => (true, &lexical[1..]),
+        Some(b'+') => (false, &lexical[1..]),
+        _ => (false, lexical),
+    };
+    let (integer, fraction) = match digits.find('.') {
+        Some(dot) => (&digits[..dot], &digits[dot + 1..]),
+        None => (digits, ""),
+    };
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    if integer.is_empty() && fraction.is_empty() {
+        return Some("0".to_string());
+    }
+    let sign = if negative { "-" } else { "" };
+    Some(format!("{}{}.{}", sign, integer, fraction))
+}
+
+/// Find the root of `x` in a union-find forest, compressing the path on the way
+fn find(parent: &mut HashMap<u64, u64>, x: u64) -> u64 {
+    let mut root = x;
+    while let Some(&p) = parent.get(&root) {
+        root = p;
+    }
+    let mut x = x;
+    while let Some(&p) = parent.get(&x) {
+        if p != root {
+            parent.insert(x, root);
+        }
+        x = p;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use sophia::term::RcTerm;
+
+    use super::canonical_decimal;
+    use crate::rules::CAX_SCO;
+    use crate::test_utils::*;
+
+    #[test]
+    fn same_as_keeps_vocabulary() {
+        let mut graph = saturate(
+            ":Bart :isA :human . :isA owl:sameAs rdf:type . :human rdfs:subClassOf :mammal .",
+            vec![Box::new(CAX_SCO)],
+        );
+        let rdftype = graph.dictionary.rdftype as u64;
+        assert_eq!(index(&graph, ":isA"), Some(rdftype));
+        assert_eq!(graph.dictionary.equivalents(rdftype)[0], rdftype);
+        assert!(has(&graph, ":Bart", "rdf:type", ":mammal"));
+        assert_eq!(graph.dictionary.add_property(&iri(":isA")) as u64, rdftype);
+    }
+
+    #[test]
+    fn canonical_decimals() {
+        assert_eq!(canonical_decimal("+001.500"), Some("1.5".to_string()));
+        assert_eq!(canonical_decimal("-0.0"), Some("0".to_string()));
+        assert_eq!(canonical_decimal("10"), canonical_decimal("10.0"));
+        assert_eq!(canonical_decimal("1e3"), None);
+        assert_eq!(canonical_decimal("."), None);
+    }
+
+    #[test]
+    fn distinct_literals() {
+        let graph = graph(
+            r#"
+            :Bart :age "10"^^xsd:integer .
+            :Bart :age "010"^^xsd:integer .
+            :Bart :age "10.0"^^xsd:decimal .
+            :Bart :age "10" .
+            :Bart :age "1e1"^^xsd:double .
+            :Bart :age "10.0"^^xsd:double .
+            "#,
+        );
+        let literal = |value: &str, datatype: &str| {
+            let term = RcTerm::new_literal_dt(value, iri(datatype)).unwrap();
+            graph.dictionary.get_index(&term).unwrap()
+        };
+        let ten = literal("10", "xsd:integer");
+        let dictionary = &graph.dictionary;
+        assert!(!dictionary.distinct_literals(ten, literal("010", "xsd:integer")));
+        assert!(!dictionary.distinct_literals(ten, literal("10.0", "xsd:decimal")));
+        assert!(dictionary.distinct_literals(ten, literal("10", "xsd:string")));
+        // the values of floating-point numbers are not known
+        let double = literal("1e1", "xsd:double");
+        assert!(!dictionary.distinct_literals(double, literal("10.0", "xsd:double")));
+        assert!(!dictionary.distinct_literals(ten, double));
+        assert!(!dictionary.distinct_literals(ten, index(&graph, ":Bart").unwrap()));
     }
 }
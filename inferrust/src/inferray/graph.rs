@@ -1,150 +1,248 @@
 use sophia::graph::inmem::*;
 use sophia::graph::GTripleSource;
 use sophia::graph::Graph;
-use sophia::term::{Term, TermData};
+use sophia::term::{ArcTerm, Term, TermData};
 use sophia::triple::streaming_mode::{ByTermRefs, StreamedTriple};
 use sophia::triple::{stream::TripleSource, Triple};
 
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::iter;
+use std::mem;
+use std::sync::Arc;
 
+use crate::rules::RuleSet;
+
+use super::Justification;
 use super::NodeDictionary;
 use super::TripleStore;
 
+/// A triple of an `InfGraph`, as yielded by its `Graph` implementation
+type StreamedTripleResult<'a> = Result<StreamedTriple<'a, ByTermRefs<Arc<str>>>, Infallible>;
+
 pub struct InfGraph {
     pub dictionary: NodeDictionary,
+    /// The justifications of the inferred triples, if they are recorded
+    provenance: Option<HashMap<[u64; 3], Justification>>,
+}
+
+/// The derivation of a triple, as returned by [`InfGraph::explain`](struct.InfGraph.html#method.explain)
+#[derive(Clone, Debug)]
+pub struct Derivation<'a> {
+    pub triple: [&'a ArcTerm; 3],
+    /// The rule inferring the triple,
+    /// `None` if it has been asserted (or if its justification has not been recorded)
+    pub rule: Option<&'a str>,
+    /// The derivations of the premises used by the rule
+    pub premises: Vec<Derivation<'a>>,
 }
 
+/// The triples of an `InfGraph` use the canonical term of each `owl:sameAs` clique.
+///
+/// The terms given to the `triples_with_*` methods may be any member of a clique:
+/// the triples yielded for a member are those of the clique,
+/// still written with its canonical term,
+/// e.g. `triples_with_s(:MargeSimpson)` yields `:Marge :hair :blue`
+/// if `:Marge` is the canonical term of `:MargeSimpson`.
 impl Graph for InfGraph {
-    type Triple = ByTermRefs<std::rc::Rc<str>>;
+    type Triple = ByTermRefs<Arc<str>>;
     type Error = Infallible;
 
     fn triples(&self) -> GTripleSource<Self> {
-        let mut v: Vec<Result<StreamedTriple<ByTermRefs<std::rc::Rc<str>>>, Self::Error>> =
-            Vec::new();
-        // eprintln!(":{:?}", self.dictionary.ts.elem);
-        for (ip, chunk) in (&self.dictionary.ts.elem).iter().enumerate() {
-            // eprintln!("p: {}", p);
-            if !chunk[0].is_empty() {
-                let ip = NodeDictionary::idx_to_prop_idx(ip);
-                let p = self.dictionary.get_term(ip);
-                for pair in &chunk[0] {
-                    // eprintln!("pso: {} {:?}", ip, pair);
-                    let s = self.dictionary.get_term(pair[0]);
-                    let o = self.dictionary.get_term(pair[1]);
-                    v.push(Ok(StreamedTriple::by_term_refs(s, p, o)));
-                }
-            }
-        }
-        Box::from(v.into_iter())
+        let chunks = self.dictionary.ts.elem.iter().enumerate();
+        Box::new(chunks.flat_map(move |(idx, chunk)| self.stream_pairs(idx, 0, &chunk[0])))
     }
 
     fn triples_with_s<'s, T>(&'s self, s: &'s Term<T>) -> GTripleSource<'s, Self>
     where
         T: TermData,
     {
-        let mut v: Vec<Result<StreamedTriple<ByTermRefs<std::rc::Rc<str>>>, Self::Error>> =
-            Vec::new();
-        if let Some(is) = self.dictionary.get_index(s) {
-            let s = self.dictionary.get_term(is);
-            for (idx, chunk) in self.dictionary.ts.elem.iter().enumerate() {
-                let chunk = &chunk[0];
-                let chunk_len = chunk.len();
-                if !chunk.is_empty() && chunk[0][0] <= is && chunk[chunk_len - 1][0] >= is {
-                    let ip = NodeDictionary::idx_to_prop_idx(idx);
-                    let p = self.dictionary.get_term(ip);
-                    for pair in chunk {
-                        if pair[0] == is {
-                            let o = self.dictionary.get_term(pair[1]);
-                            v.push(Ok(StreamedTriple::by_term_refs(s, p, o)));
-                        }
-                    }
-                }
+        let is = match self.dictionary.get_index(s) {
+            Some(is) => is,
+            None => return Box::new(iter::empty()),
+        };
+        // the pairs of each chunk are sorted by subject,
+        // so the run of `is` is found (or found missing) by binary search
+        let ts = &self.dictionary.ts;
+        Box::new(
+            self.used_chunks()
+                .flat_map(move |idx| self.stream_pairs(idx, 0, ts.pairs_with(idx, 0, is))),
+        )
+    }
+
+    fn triples_with_p<'s, T>(&'s self, p: &'s Term<T>) -> GTripleSource<'s, Self>
+    where
+        T: TermData,
+    {
+        match self.chunk_index(p) {
+            Some(idx) => Box::new(self.stream_pairs(idx, 0, self.dictionary.ts.pairs(idx, 0))),
+            None => Box::new(iter::empty()),
+        }
+    }
+
+    fn triples_with_o<'s, T>(&'s self, o: &'s Term<T>) -> GTripleSource<'s, Self>
+    where
+        T: TermData,
+    {
+        let io = match self.dictionary.get_index(o) {
+            Some(io) => io,
+            None => return Box::new(iter::empty()),
+        };
+        let ts = &self.dictionary.ts;
+        Box::new(
+            self.used_chunks()
+                .flat_map(move |idx| self.stream_pairs(idx, 1, ts.pairs_with(idx, 1, io))),
+        )
+    }
+
+    fn triples_with_sp<'s, T, U>(
+        &'s self,
+        s: &'s Term<T>,
+        p: &'s Term<U>,
+    ) -> GTripleSource<'s, Self>
+    where
+        T: TermData,
+        U: TermData,
+    {
+        match (self.dictionary.get_index(s), self.chunk_index(p)) {
+            (Some(is), Some(idx)) => {
+                Box::new(self.stream_pairs(idx, 0, self.dictionary.ts.pairs_with(idx, 0, is)))
             }
+            _ => Box::new(iter::empty()),
         }
-        Box::from(v.into_iter())
     }
 
-    fn triples_with_p<'s, T>(&'s self, p: &'s Term<T>) -> GTripleSource<'s, Self>
+    fn triples_with_so<'s, T, U>(
+        &'s self,
+        s: &'s Term<T>,
+        o: &'s Term<U>,
+    ) -> GTripleSource<'s, Self>
     where
         T: TermData,
+        U: TermData,
     {
-        println!("My impl");
-        let mut v: Vec<Result<StreamedTriple<ByTermRefs<std::rc::Rc<str>>>, Self::Error>> =
-            Vec::new();
-        if let Some(ip) = self.dictionary.get_index(p) {
-            let idx = NodeDictionary::prop_idx_to_idx(ip);
-            let chunk = &self.dictionary.ts.elem[idx];
-            if !chunk[0].is_empty() {
-                let p = self.dictionary.get_term(ip);
-                for pair in &chunk[0] {
-                    let s = self.dictionary.get_term(pair[0]);
-                    let o = self.dictionary.get_term(pair[1]);
-                    v.push(Ok(StreamedTriple::by_term_refs(s, p, o)));
-                }
+        let (is, io) = match (self.dictionary.get_index(s), self.dictionary.get_index(o)) {
+            (Some(is), Some(io)) => (is, io),
+            _ => return Box::new(iter::empty()),
+        };
+        let ts = &self.dictionary.ts;
+        Box::new(
+            self.used_chunks()
+                .filter(move |&idx| ts.contains_pair(idx, [is, io]))
+                .map(move |idx| self.streamed_triple(is, idx, io)),
+        )
+    }
+
+    fn triples_with_po<'s, T, U>(
+        &'s self,
+        p: &'s Term<T>,
+        o: &'s Term<U>,
+    ) -> GTripleSource<'s, Self>
+    where
+        T: TermData,
+        U: TermData,
+    {
+        match (self.chunk_index(p), self.dictionary.get_index(o)) {
+            (Some(idx), Some(io)) => {
+                Box::new(self.stream_pairs(idx, 1, self.dictionary.ts.pairs_with(idx, 1, io)))
             }
+            _ => Box::new(iter::empty()),
         }
-        Box::from(v.into_iter())
-    }
-
-    //     fn triples_with_o<'s, T>(&'s self, o: &'s Term<T>) -> GTripleSource<'s, Self>
-    //     where
-    //         T: TermData,
-    //     {
-    //         Box::new(self.triples().filter_ok(move |t| t.o() == o))
-    //     }
-
-    //     fn triples_with_sp<'s, T, U>(
-    //         &'s self,
-    //         s: &'s Term<T>,
-    //         p: &'s Term<U>,
-    //     ) -> GTripleSource<'s, Self>
-    //     where
-    //         T: TermData,
-    //         U: TermData,
-    //     {
-    //         Box::new(self.triples_with_s(s).filter_ok(move |t| t.p() == p))
-    //     }
-
-    //     fn triples_with_so<'s, T, U>(
-    //         &'s self,
-    //         s: &'s Term<T>,
-    //         o: &'s Term<U>,
-    //     ) -> GTripleSource<'s, Self>
-    //     where
-    //         T: TermData,
-    //         U: TermData,
-    //     {
-    //         Box::new(self.triples_with_s(s).filter_ok(move |t| t.o() == o))
-    //     }
-
-    //     fn triples_with_po<'s, T, U>(
-    //         &'s self,
-    //         p: &'s Term<T>,
-    //         o: &'s Term<U>,
-    //     ) -> GTripleSource<'s, Self>
-    //     where
-    //         T: TermData,
-    //         U: TermData,
-    //     {
-    //         Box::new(self.triples_with_p(p).filter_ok(move |t| t.o() == o))
-    //     }
-
-    //     fn triples_with_spo<'s, T, U, V>(
-    //         &'s self,
-    //         s: &'s Term<T>,
-    //         p: &'s Term<U>,
-    //         o: &'s Term<V>,
-    //     ) -> GTripleSource<'s, Self>
-    //     where
-    //         T: TermData,
-    //         U: TermData,
-    //         V: TermData,
-    //     {
-    //         Box::new(self.triples_with_sp(s, p).filter_ok(move |t| t.o() == o))
-    //     }
+    }
+
+    fn triples_with_spo<'s, T, U, V>(
+        &'s self,
+        s: &'s Term<T>,
+        p: &'s Term<U>,
+        o: &'s Term<V>,
+    ) -> GTripleSource<'s, Self>
+    where
+        T: TermData,
+        U: TermData,
+        V: TermData,
+    {
+        let found = match (
+            self.dictionary.get_index(s),
+            self.chunk_index(p),
+            self.dictionary.get_index(o),
+        ) {
+            (Some(is), Some(idx), Some(io)) if self.dictionary.ts.contains_pair(idx, [is, io]) => {
+                Some(self.streamed_triple(is, idx, io))
+            }
+            _ => None,
+        };
+        Box::new(found.into_iter())
+    }
 }
 
 impl InfGraph {
+    /// Return the indexes of the chunks of the store which contain some pairs
+    fn used_chunks(&self) -> impl Iterator<Item = usize> + '_ {
+        self.dictionary
+            .ts
+            .elem
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| !chunk[0].is_empty())
+            .map(|(idx, _)| idx)
+    }
+
+    /// Return the index of the chunk of property `p` in the store, if it is a property of this graph
+    fn chunk_index<T>(&self, p: &Term<T>) -> Option<usize>
+    where
+        T: TermData,
+    {
+        let ip = self.dictionary.get_index(p)?;
+        if NodeDictionary::is_property(ip) {
+            Some(NodeDictionary::prop_idx_to_idx(ip))
+        } else {
+            None
+        }
+    }
+
+    /// Return the triple `s p o`, where `p` is the property of chunk `idx`
+    fn streamed_triple(&self, s: u64, idx: usize, o: u64) -> StreamedTripleResult<'_> {
+        Ok(StreamedTriple::by_term_refs(
+            self.dictionary.get_term(s),
+            self.dictionary
+                .get_term(NodeDictionary::idx_to_prop_idx(idx)),
+            self.dictionary.get_term(o),
+        ))
+    }
+
+    /// Lazily resolve the triples of chunk `idx` from `pairs`,
+    /// taken from its subject-object (`order == 0`) or object-subject (`order == 1`) vector
+    fn stream_pairs<'s>(
+        &'s self,
+        idx: usize,
+        order: usize,
+        pairs: &'s [[u64; 2]],
+    ) -> impl Iterator<Item = StreamedTripleResult<'s>> + 's {
+        // the property is only resolved if the chunk has some pairs
+        let p = if pairs.is_empty() {
+            None
+        } else {
+            Some(
+                self.dictionary
+                    .get_term(NodeDictionary::idx_to_prop_idx(idx)),
+            )
+        };
+        p.into_iter().flat_map(move |p| {
+            pairs.iter().map(move |pair| {
+                let (s, o) = if order == 0 {
+                    (pair[0], pair[1])
+                } else {
+                    (pair[1], pair[0])
+                };
+                Ok(StreamedTriple::by_term_refs(
+                    self.dictionary.get_term(s),
+                    p,
+                    self.dictionary.get_term(o),
+                ))
+            })
+        })
+    }
+
     fn encode_triple<TD>(&mut self, t: &dyn Triple<TermData = TD>) -> [u64; 3]
     where
         TD: std::convert::AsRef<str> + std::clone::Clone + std::cmp::Eq + std::hash::Hash,
@@ -164,6 +262,10 @@ impl InfGraph {
                     s = self.dictionary.add_property(ts).into();
                     o = self.dictionary.add(to);
                 }
+                2 => {
+                    s = self.dictionary.add(ts);
+                    o = self.dictionary.add_property(to).into();
+                }
                 3 => {
                     s = self.dictionary.add_property(ts).into();
                     o = self.dictionary.add_property(to).into();
@@ -174,13 +276,476 @@ impl InfGraph {
             // Add a regular triple
             s = self.dictionary.add(ts);
             o = self.dictionary.add(to);
+            // Subjects typed as properties will always be properties
+            if p == self.dictionary.rdftype
+                && (o == self.dictionary.rdfProperty as u64
+                    || o == self.dictionary.rdfsContainerMembershipProperty as u64)
+            {
+                s = self.dictionary.add_property(ts).into();
+            }
         }
         [s, p as u64, o]
     }
 
+    /// Add the triples of `triples` to this graph, and infer their consequences
+    ///
+    /// The graph must already be saturated by `rules`:
+    /// only the consequences of the new triples are computed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inferrust::inferray::*;
+    /// use inferrust::rules::*;
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    ///
+    ///     :human rdfs:subClassOf :mammal .
+    ///     :mammal rdfs:subClassOf :animal .
+    /// "#;
+    /// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+    /// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
+    /// rules.fire_all(&mut graph);
+    /// assert_eq!(graph.size(), 3);
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///
+    ///     :Bart a :human .
+    /// "#;
+    /// graph.insert_and_reason(sophia::parser::turtle::parse_str(rep), &mut rules);
+    /// assert_eq!(graph.size(), 6);
+    /// ```
+    pub fn insert_and_reason<TS, R>(&mut self, mut triples: TS, rules: &mut R)
+    where
+        TS: TripleSource,
+        R: RuleSet,
+    {
+        // the new triples are encoded in a store of their own,
+        // so that the resources turned into properties are remapped in both stores
+        let mut store = mem::take(&mut self.dictionary.ts);
+        let remapped = self.dictionary.remapped().len();
+        triples
+            .for_each_triple(|t| {
+                self.encode_triple(&t);
+                // the asserted triples are given with the indexes of their own terms,
+                // see NodeDictionary::add_asserted
+                let dictionary = &self.dictionary;
+                let index = |term| dictionary.get_raw_index(term).expect("Err");
+                let rep = [index(t.s()), index(t.p()), index(t.o())];
+                self.dictionary.ts.add_triple(rep);
+            })
+            .expect("Streaming error");
+        let remap: HashMap<u64, u64> = self.dictionary.remapped()[remapped..]
+            .iter()
+            .map(|&(res, prop)| (res, prop as u64))
+            .collect();
+        for (&res, &prop) in &remap {
+            store.res_to_prop(res, prop as u32);
+        }
+        if !remap.is_empty() {
+            remap_justifications(&mut self.provenance, |index| {
+                *remap.get(&index).unwrap_or(&index)
+            });
+        }
+        let mut added = mem::replace(&mut self.dictionary.ts, store);
+        added.sort();
+        if self.dictionary.remapped().len() > remapped {
+            self.dictionary.asserted.sort();
+            if let Some(unmerged) = &mut self.dictionary.unmerged {
+                unmerged.sort();
+            }
+        }
+        let added = self.dictionary.add_asserted(&added);
+        if self.dictionary.remapped().len() > remapped {
+            // the order of the store is broken, and so is the saturation
+            self.dictionary.ts.add_all(added);
+            self.dictionary.ts.sort();
+            rules.fire_all(self);
+        } else {
+            let added = added.difference(&self.dictionary.ts);
+            self.dictionary.ts.merge(&added);
+            rules.fire_added(self, added);
+        }
+    }
+
+    /// Add the RDF and RDFS axiomatic triples to this graph, and saturate it with `rules`
+    ///
+    /// The graph does not need to be saturated beforehand.
+    /// The axiomatic triples are considered as asserted.
+    /// See [`NodeDictionary::axiomatic_triples`](struct.NodeDictionary.html#method.axiomatic_triples).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inferrust::inferray::*;
+    /// use inferrust::rules::*;
+    /// use sophia::ns::{rdf, rdfs};
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///
+    ///     :Bart :label "Bart" .
+    /// "#;
+    /// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+    /// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::RDFSFull);
+    /// graph.insert_axiomatic_triples(&mut rules);
+    ///
+    /// let index = |term| graph.dictionary.get_index(term).unwrap();
+    /// let rdftype = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64);
+    /// assert!(graph.dictionary.ts.contains_pair(rdftype, [index(&rdf::type_), index(&rdf::Property)]));
+    /// assert!(graph.dictionary.ts.contains_pair(rdftype, [index(&rdfs::Class), index(&rdfs::Class)]));
+    /// ```
+    pub fn insert_axiomatic_triples<R>(&mut self, rules: &mut R)
+    where
+        R: RuleSet,
+    {
+        let mut added = TripleStore::new();
+        for triple in self.dictionary.axiomatic_triples() {
+            added.add_triple(triple);
+        }
+        added.sort();
+        let added = self.dictionary.add_asserted(&added);
+        self.dictionary.ts.merge(&added);
+        rules.fire_all(self);
+    }
+
+    /// Remove the asserted triples of `triples` from this graph,
+    /// and retract their consequences
+    ///
+    /// The graph must already be saturated by `rules`.
+    /// The inferred triples which can still be derived from the remaining ones are kept,
+    /// as well as those which have been asserted too.
+    /// See [`RuleSet::fire_removed`](../rules/trait.RuleSet.html#tymethod.fire_removed).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inferrust::inferray::*;
+    /// use inferrust::rules::*;
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    ///
+    ///     :Bart a :human .
+    ///     :human rdfs:subClassOf :mammal .
+    ///     :mammal rdfs:subClassOf :animal .
+    /// "#;
+    /// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+    /// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
+    /// rules.fire_all(&mut graph);
+    /// assert_eq!(graph.size(), 6);
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    ///
+    ///     :mammal rdfs:subClassOf :animal .
+    /// "#;
+    /// graph.delete_and_reason(sophia::parser::turtle::parse_str(rep), &mut rules);
+    /// assert_eq!(graph.size(), 3);
+    /// ```
+    pub fn delete_and_reason<TS, R>(&mut self, mut triples: TS, rules: &mut R)
+    where
+        TS: TripleSource,
+        R: RuleSet,
+    {
+        let mut removed = TripleStore::new();
+        let dictionary = &self.dictionary;
+        triples
+            .for_each_triple(|t| {
+                let s = dictionary.get_raw_index(t.s());
+                let p = dictionary.get_raw_index(t.p());
+                let o = dictionary.get_raw_index(t.o());
+                if let (Some(s), Some(p), Some(o)) = (s, p, o) {
+                    if NodeDictionary::is_property(p) {
+                        removed.add_triple([s, p, o]);
+                    }
+                }
+            })
+            .expect("Streaming error");
+        removed.sort();
+        let removed = self.dictionary.remove_asserted(&removed);
+        rules.fire_removed(self, removed);
+    }
+
+    /// Return the triples of this graph which have been asserted
+    ///
+    /// Like every triple of the graph, they use the canonical term of each `owl:sameAs` clique.
+    pub fn asserted_triples(&self) -> GTripleSource<Self> {
+        let chunks = self.dictionary.asserted.elem.iter().enumerate();
+        Box::new(chunks.flat_map(move |(idx, chunk)| self.stream_pairs(idx, 0, &chunk[0])))
+    }
+
+    /// Return the triples of this graph which have been inferred, and not asserted
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inferrust::inferray::*;
+    /// use inferrust::rules::*;
+    /// use sophia::term::RcTerm;
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    ///
+    ///     :Bart a :human .
+    ///     :human rdfs:subClassOf :mammal .
+    /// "#;
+    /// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+    /// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
+    /// rules.fire_all(&mut graph);
+    /// assert_eq!(graph.asserted_triples().count(), 2);
+    /// assert_eq!(graph.inferred_triples().count(), 1);
+    ///
+    /// let bart = RcTerm::new_iri("http://example.org/Bart").unwrap();
+    /// let mammal = RcTerm::new_iri("http://example.org/mammal").unwrap();
+    /// assert!(graph.is_inferred(&bart, &sophia::ns::rdf::type_, &mammal));
+    /// ```
+    pub fn inferred_triples(&self) -> GTripleSource<Self> {
+        let asserted = &self.dictionary.asserted;
+        let chunks = self.dictionary.ts.elem.iter().enumerate();
+        Box::new(chunks.flat_map(move |(idx, chunk)| {
+            chunk[0]
+                .iter()
+                .filter(move |pair| !asserted.contains_pair(idx, **pair))
+                .map(move |pair| self.streamed_triple(pair[0], idx, pair[1]))
+        }))
+    }
+
+    /// Return `true` if the given triple has been inferred, and not asserted
+    pub fn is_inferred<T, U, V>(&self, s: &Term<T>, p: &Term<U>, o: &Term<V>) -> bool
+    where
+        T: TermData,
+        U: TermData,
+        V: TermData,
+    {
+        let is = self.dictionary.get_index(s);
+        let ip = self.dictionary.get_index(p);
+        let io = self.dictionary.get_index(o);
+        match (is, ip, io) {
+            (Some(is), Some(ip), Some(io)) if NodeDictionary::is_property(ip) => {
+                let idx = NodeDictionary::prop_idx_to_idx(ip);
+                self.dictionary.ts.contains_pair(idx, [is, io])
+                    && !self.dictionary.asserted.contains_pair(idx, [is, io])
+            }
+            _ => false,
+        }
+    }
+
+    /// Return `true` if the justifications of the inferred triples are recorded
+    pub fn provenance_enabled(&self) -> bool {
+        self.provenance.is_some()
+    }
+
+    /// Record the justification of every triple inferred from now on,
+    /// so that it can be [explained](#method.explain)
+    ///
+    /// This slows reasoning down, and uses more memory.
+    pub fn enable_provenance(&mut self) {
+        if self.provenance.is_none() {
+            self.provenance = Some(HashMap::new());
+        }
+    }
+
+    /// Return an empty store for the triples inferred by a rule from this graph,
+    /// which records their premises if the provenance is enabled
+    pub fn new_output(&self) -> TripleStore {
+        if self.provenance_enabled() {
+            TripleStore::with_premises()
+        } else {
+            TripleStore::new()
+        }
+    }
+
+    /// Record the justifications of the triples of `inferred`, unless they have one already
+    pub(crate) fn justify(
+        &mut self,
+        inferred: &TripleStore,
+        mut justifications: HashMap<[u64; 3], Justification>,
+    ) {
+        if let Some(provenance) = &mut self.provenance {
+            for (idx, chunk) in inferred.elem.iter().enumerate() {
+                let ip = NodeDictionary::idx_to_prop_idx(idx);
+                for pair in &chunk[0] {
+                    let triple = [pair[0], ip, pair[1]];
+                    if let Some(justification) = justifications.remove(&triple) {
+                        provenance.entry(triple).or_insert(justification);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Forget the justifications of the triples of `deleted`
+    pub(crate) fn unjustify(&mut self, deleted: &TripleStore) {
+        if let Some(provenance) = &mut self.provenance {
+            for (idx, chunk) in deleted.elem.iter().enumerate() {
+                let ip = NodeDictionary::idx_to_prop_idx(idx);
+                for pair in &chunk[0] {
+                    provenance.remove(&[pair[0], ip, pair[1]]);
+                }
+            }
+        }
+    }
+
+    /// Undo the merges of the `owl:sameAs` cliques, forgetting every inferred triple
+    ///
+    /// Return `false` if no clique has been merged, in which case nothing is done.
+    /// Otherwise, the graph must be saturated again.
+    pub(crate) fn unmerge_same_as(&mut self) -> bool {
+        if !self.dictionary.unmerge_same_as() {
+            return false;
+        }
+        if let Some(provenance) = &mut self.provenance {
+            provenance.clear();
+        }
+        true
+    }
+
+    /// Replace every index of the justifications by its canonical index,
+    /// after the `owl:sameAs` cliques have been merged
+    pub(crate) fn canonicalize_provenance(&mut self) {
+        let dictionary = &self.dictionary;
+        remap_justifications(&mut self.provenance, |index| dictionary.canonical(index));
+    }
+
+    /// Explain how the given triple has been inferred
+    ///
+    /// Return `None` if the triple is not in this graph.
+    /// The justifications are only available for the triples inferred
+    /// after the provenance has been [enabled](#method.enable_provenance).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use inferrust::inferray::*;
+    /// use inferrust::rules::*;
+    /// use sophia::term::RcTerm;
+    ///
+    /// let rep = r#"
+    ///     @prefix : <http://example.org/> .
+    ///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    ///
+    ///     :Bart a :human .
+    ///     :human rdfs:subClassOf :mammal .
+    ///     :mammal rdfs:subClassOf :animal .
+    /// "#;
+    /// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+    /// graph.enable_provenance();
+    /// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
+    /// rules.fire_all(&mut graph);
+    ///
+    /// let bart = RcTerm::new_iri("http://example.org/Bart").unwrap();
+    /// let animal = RcTerm::new_iri("http://example.org/animal").unwrap();
+    /// let derivation = graph.explain(&bart, &sophia::ns::rdf::type_, &animal).unwrap();
+    /// assert_eq!(derivation.rule, Some("CAX-SCO"));
+    /// assert_eq!(derivation.premises.len(), 2);
+    /// ```
+    pub fn explain<T, U, V>(&self, s: &Term<T>, p: &Term<U>, o: &Term<V>) -> Option<Derivation>
+    where
+        T: TermData,
+        U: TermData,
+        V: TermData,
+    {
+        let is = self.dictionary.get_index(s)?;
+        let ip = self.dictionary.get_index(p)?;
+        let io = self.dictionary.get_index(o)?;
+        if !NodeDictionary::is_property(ip)
+            || !self
+                .dictionary
+                .ts
+                .contains_pair(NodeDictionary::prop_idx_to_idx(ip), [is, io])
+        {
+            return None;
+        }
+        Some(self.derivation([is, ip, io], &mut Vec::new()))
+    }
+
+    /// Build the derivation of `triple`,
+    /// ignoring the premises already being derived in `path`, to avoid cycles
+    fn derivation(&self, triple: [u64; 3], path: &mut Vec<[u64; 3]>) -> Derivation {
+        let [is, ip, io] = triple;
+        let terms = [
+            self.dictionary.get_term(is),
+            self.dictionary.get_term(ip),
+            self.dictionary.get_term(io),
+        ];
+        let asserted = self
+            .dictionary
+            .asserted
+            .contains_pair(NodeDictionary::prop_idx_to_idx(ip), [is, io]);
+        let justification = match &self.provenance {
+            Some(provenance) if !asserted => provenance.get(&triple),
+            _ => None,
+        };
+        match justification {
+            Some(justification) => {
+                path.push(triple);
+                let mut premises = Vec::with_capacity(justification.premises.len());
+                for premise in &justification.premises {
+                    if !path.contains(premise) {
+                        premises.push(self.derivation(*premise, path));
+                    }
+                }
+                path.pop();
+                Derivation {
+                    triple: terms,
+                    rule: Some(&justification.rule),
+                    premises,
+                }
+            }
+            None => Derivation {
+                triple: terms,
+                rule: None,
+                premises: Vec::new(),
+            },
+        }
+    }
+
     pub fn size(&mut self) -> usize {
         self.dictionary.ts.size()
     }
+
+    /// Return all the terms known to be `owl:sameAs` the given one (including itself)
+    ///
+    /// The first one is the canonical term, which represents the others in the store.
+    pub fn equivalents<T>(&self, t: &Term<T>) -> Vec<&ArcTerm>
+    where
+        T: TermData,
+    {
+        match self.dictionary.get_index(t) {
+            Some(index) => self
+                .dictionary
+                .equivalents(index)
+                .into_iter()
+                .map(|index| self.dictionary.get_term(index))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Replace every index of the justifications by its image through `map`
+fn remap_justifications<F>(provenance: &mut Option<HashMap<[u64; 3], Justification>>, map: F)
+where
+    F: Fn(u64) -> u64,
+{
+    if let Some(justifications) = provenance.take() {
+        let remap = |triple: [u64; 3]| [map(triple[0]), map(triple[1]), map(triple[2])];
+        let mut remapped = HashMap::with_capacity(justifications.len());
+        for (triple, mut justification) in justifications {
+            for premise in justification.premises.iter_mut() {
+                *premise = remap(*premise);
+            }
+            remapped.entry(remap(triple)).or_insert(justification);
+        }
+        *provenance = Some(remapped);
+    }
 }
 
 impl<TS> From<TS> for InfGraph
@@ -190,7 +755,10 @@ where
     fn from(mut ts: TS) -> Self {
         let store = TripleStore::new();
         let dictionary = NodeDictionary::new(store);
-        let mut me = Self { dictionary };
+        let mut me = Self {
+            dictionary,
+            provenance: None,
+        };
         ts.for_each_triple(|t| {
             let rep = me.encode_triple(&t);
             //eprintln!("{:?}", rep);
@@ -198,15 +766,22 @@ where
         })
         .expect("Streaming error");
         me.dictionary.ts.sort();
+        me.dictionary.asserted = me.dictionary.ts.clone();
         me
     }
 }
 
 // Should return -1 if both s and o are res,
 // 1 if s is prop and o is res,
+// 2 if s is res and o is prop,
 // and 3 if both s and o are prop
 fn contains_prop_in_s_or_o(property_index: u32, dictionary: &NodeDictionary) -> i32 {
-    let prop_in_s = vec![dictionary.rdfsdomain, dictionary.rdfsrange];
+    let prop_in_s = vec![
+        dictionary.rdfsdomain,
+        dictionary.rdfsrange,
+        dictionary.propertyChainAxiom,
+    ];
+    let prop_in_o = vec![dictionary.onProperty];
     let prop_in_s_and_o = vec![
         dictionary.owlequivalentProperty,
         dictionary.owlinverseOf,
@@ -217,7 +792,117 @@ fn contains_prop_in_s_or_o(property_index: u32, dictionary: &NodeDictionary) ->
         3
     } else if prop_in_s.contains(&property_index) {
         1
+    } else if prop_in_o.contains(&property_index) {
+        2
     } else {
         -1
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sophia::graph::Graph;
+    use sophia::triple::Triple;
+
+    use crate::rules::Profile;
+    use crate::test_utils::*;
+
+    /// Check that removing `deleted` from the saturation of `remaining` and `deleted`
+    /// gives the saturation of `remaining`
+    fn check_deletion(remaining: &str, deleted: &str) {
+        let mut rules = Profile::OWL2RL.rules();
+        let mut graph = saturate(
+            &format!("{}{}", remaining, deleted),
+            Profile::OWL2RL.rules(),
+        );
+        delete(&mut graph, deleted, &mut rules);
+        let expected = saturate_profile(remaining, Profile::OWL2RL);
+        assert_eq!(entailed(&graph), entailed(&expected));
+    }
+
+    #[test]
+    fn axiomatic_triples_saturate() {
+        let mut graph = graph(r#":Bart :label "Bart" ."#);
+        graph.insert_axiomatic_triples(&mut Profile::RDFSFull.rules());
+        assert!(has(&graph, ":label", "rdf:type", "rdf:Property"));
+        assert!(has(&graph, "rdf:type", "rdf:type", "rdf:Property"));
+    }
+
+    #[test]
+    fn delete_rederives() {
+        // :Bart a :mammal has another derivation
+        check_deletion(
+            r#"
+            :Bart a :pet .
+            :human rdfs:subClassOf :mammal .
+            :pet rdfs:subClassOf :mammal .
+            "#,
+            ":Bart a :human .",
+        );
+        check_deletion(
+            ":a rdfs:subClassOf :b . :b rdfs:subClassOf :c . :a rdfs:subClassOf :d . :d rdfs:subClassOf :c .",
+            ":a rdfs:subClassOf :e . :e rdfs:subClassOf :c .",
+        );
+    }
+
+    #[test]
+    fn delete_same_as() {
+        check_deletion(
+            r#":Marge :age "36" . :MargeSimpson :hair :blue . :Bart :mother :Marge ."#,
+            ":Marge owl:sameAs :MargeSimpson .",
+        );
+    }
+
+    #[test]
+    fn delete_inferred_same_as() {
+        check_deletion(
+            ":mother a owl:FunctionalProperty . :Bart :mother :Marge . :MargeSimpson :hair :blue .",
+            ":Bart :mother :MargeSimpson .",
+        );
+        check_deletion(
+            r#"
+            :OneMother owl:maxCardinality "1"^^xsd:nonNegativeInteger .
+            :OneMother owl:onProperty :mother .
+            :Bart a :child . :Bart :mother :Marge, :MargeSimpson .
+            "#,
+            ":child rdfs:subClassOf :OneMother .",
+        );
+    }
+
+    #[test]
+    fn delete_member_triple() {
+        check_deletion(
+            ":Marge owl:sameAs :MargeSimpson . :MargeSimpson :hair :blue .",
+            ":Marge :hair :blue .",
+        );
+        check_deletion(
+            ":MargeSimpson :hair :blue . :Bart :mother :Marge .",
+            ":Marge :hair :blue . :Marge owl:sameAs :MargeSimpson .",
+        );
+    }
+
+    #[test]
+    fn lookup_same_as_member() {
+        let graph = saturate_profile(
+            ":Marge owl:sameAs :MargeSimpson . :MargeSimpson :hair :blue . :Bart :mother :Marge .",
+            Profile::RDFSPlus,
+        );
+        let canonical = graph
+            .dictionary
+            .get_term(index(&graph, ":MargeSimpson").unwrap());
+        for name in &[":Marge", ":MargeSimpson"] {
+            let member = iri(name);
+            let hair = graph
+                .triples_with_sp(&member, &iri(":hair"))
+                .map(|t| t.unwrap().s().value())
+                .collect::<Vec<_>>();
+            assert_eq!(hair, vec![canonical.value()]);
+            let mother = graph
+                .triples_with_o(&member)
+                .filter_map(|t| t.ok())
+                .filter(|t| t.p().value() == iri(":mother").value())
+                .count();
+            assert_eq!(mother, 1);
+        }
+    }
+}
@@ -1,15 +1,80 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::mem;
 
 use super::NodeDictionary;
 
+#[derive(Clone, Default)]
 pub struct TripleStore {
     pub elem: Vec<[Vec<[u64; 2]>; 2]>,
+    /// The premises of the triples added with `add_inferred_triple`, if they are recorded
+    premises: Option<Vec<([u64; 3], Vec<[u64; 3]>)>>,
+}
+
+/// The justification of an inferred triple: the rule inferring it, and the premises it used
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Justification {
+    pub rule: String,
+    pub premises: Vec<[u64; 3]>,
 }
 
 impl TripleStore {
     pub fn new() -> Self {
         let elem = Vec::new();
-        Self { elem }
+        Self {
+            elem,
+            premises: None,
+        }
+    }
+
+    /// Return an empty store recording the premises of the triples added with
+    /// [`add_inferred_triple`](#method.add_inferred_triple)
+    pub fn with_premises() -> Self {
+        Self {
+            elem: Vec::new(),
+            premises: Some(Vec::new()),
+        }
+    }
+
+    /// Return `true` if this store records the premises of its triples
+    pub fn records_premises(&self) -> bool {
+        self.premises.is_some()
+    }
+
+    /// Add a triple inferred from the given premises,
+    /// which are only recorded if this store has been created by
+    /// [`with_premises`](#method.with_premises)
+    pub fn add_inferred_triple(&mut self, triple: [u64; 3], premises: &[[u64; 3]]) {
+        if let Some(recorded) = &mut self.premises {
+            recorded.push((triple, premises.to_vec()));
+        }
+        self.add_triple(triple);
+    }
+
+    /// Return the justifications of the triples of this store, inferred by `rule`,
+    /// and stop recording premises
+    ///
+    /// The triples added without premises are justified by the rule alone.
+    pub fn take_justifications(&mut self, rule: &str) -> HashMap<[u64; 3], Justification> {
+        let mut justifications = HashMap::new();
+        for (triple, premises) in self.premises.take().unwrap_or_default() {
+            justifications.entry(triple).or_insert(Justification {
+                rule: rule.to_string(),
+                premises,
+            });
+        }
+        for (idx, chunk) in self.elem.iter().enumerate() {
+            let ip = NodeDictionary::idx_to_prop_idx(idx);
+            for pair in &chunk[0] {
+                justifications
+                    .entry([pair[0], ip, pair[1]])
+                    .or_insert_with(|| Justification {
+                        rule: rule.to_string(),
+                        premises: Vec::new(),
+                    });
+            }
+        }
+        justifications
     }
 
     pub fn add_triple(&mut self, triple: [u64; 3]) {
@@ -24,6 +89,9 @@ impl TripleStore {
     }
 
     pub fn add_all(&mut self, other: Self) {
+        if let (Some(premises), Some(other_premises)) = (&mut self.premises, other.premises) {
+            premises.extend(other_premises);
+        }
         if other.elem.len() > self.elem.len() {
             self.elem.resize_with(other.elem.len(), Default::default);
         }
@@ -34,6 +102,64 @@ impl TripleStore {
         }
     }
 
+    /// Return the pairs of property `ip`,
+    /// from the subject-object (`order == 0`) or object-subject (`order == 1`) vector
+    pub fn pairs(&self, ip: usize, order: usize) -> &[[u64; 2]] {
+        match self.elem.get(ip) {
+            Some(chunk) => &chunk[order],
+            None => &[],
+        }
+    }
+
+    /// Return `true` if property `ip` has the given subject-object pair
+    ///
+    /// # Pre-condition
+    /// `self` must be sorted
+    pub fn contains_pair(&self, ip: usize, pair: [u64; 2]) -> bool {
+        self.pairs(ip, 0).binary_search(&pair).is_ok()
+    }
+
+    /// Return the pairs of property `ip` whose first element is `first`,
+    /// taken from the subject-object (`order == 0`) or object-subject (`order == 1`) vector
+    ///
+    /// # Pre-condition
+    /// `self` must be sorted
+    pub fn pairs_with(&self, ip: usize, order: usize, first: u64) -> &[[u64; 2]] {
+        match self.elem.get(ip) {
+            Some(chunk) => {
+                let pairs = &chunk[order];
+                let start = pairs.partition_point(|pair| pair[0] < first);
+                let end = start + pairs[start..].partition_point(|pair| pair[0] == first);
+                &pairs[start..end]
+            }
+            None => &[],
+        }
+    }
+
+    /// Decode the RDF list starting at node `head`,
+    /// given the property indexes of `rdf:first` and `rdf:rest` (`first` and `rest`),
+    /// and the index of `rdf:nil` (`nil`)
+    ///
+    /// Return `None` if the list is malformed,
+    /// i.e. if one of its nodes has no `rdf:first` or no `rdf:rest`, or if it is cyclic.
+    /// If a node has several `rdf:first` or `rdf:rest`, the smallest one is used.
+    ///
+    /// # Pre-condition
+    /// `self` must be sorted
+    pub fn list(&self, head: u64, first: usize, rest: usize, nil: u64) -> Option<Vec<u64>> {
+        let mut members = Vec::new();
+        let mut visited = HashSet::new();
+        let mut node = head;
+        while node != nil {
+            if !visited.insert(node) {
+                return None;
+            }
+            members.push(self.pairs_with(first, 0, node).first()?[1]);
+            node = self.pairs_with(rest, 0, node).first()?[1];
+        }
+        Some(members)
+    }
+
     /// # Pre-condition
     /// `self.elem` must have an element at index `ip`
     #[inline]
@@ -42,30 +168,100 @@ impl TripleStore {
         self.elem[ip][1].push([io, is]);
     }
 
+    /// Return the triples of `self` which are not in `other`
+    ///
+    /// # Pre-condition
+    /// Both stores must be sorted
+    pub fn difference(&self, other: &Self) -> Self {
+        self.select(other, false)
+    }
+
+    /// Return the triples of `self` which are also in `other`
+    ///
+    /// # Pre-condition
+    /// Both stores must be sorted
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.select(other, true)
+    }
+
+    /// Return the triples of `self` which are in `other` (if `common`) or not
+    fn select(&self, other: &Self, common: bool) -> Self {
+        let mut output = Self::new();
+        for (ip, chunk) in self.elem.iter().enumerate() {
+            let other_pairs = other.pairs(ip, 0);
+            let mut j = 0;
+            for pair in &chunk[0] {
+                while j < other_pairs.len() && other_pairs[j] < *pair {
+                    j += 1;
+                }
+                if (j < other_pairs.len() && other_pairs[j] == *pair) == common {
+                    if ip >= output.elem.len() {
+                        output.elem.resize_with(ip + 1, Default::default);
+                    }
+                    output.add_triple_raw(pair[0], ip, pair[1]);
+                }
+            }
+        }
+        output.sort();
+        output
+    }
+
+    /// Add the triples of `other` to `self`, keeping it sorted
+    ///
+    /// Unlike [`add_all`](#method.add_all), only the chunks of the properties used in `other`
+    /// are rebuilt.
+    ///
+    /// # Pre-condition
+    /// Both stores must be sorted
+    pub fn merge(&mut self, other: &Self) {
+        if other.elem.len() > self.elem.len() {
+            self.elem.resize_with(other.elem.len(), Default::default);
+        }
+        for (ip, chunk) in other.elem.iter().enumerate() {
+            for (i, other_pairs) in chunk.iter().enumerate() {
+                if !other_pairs.is_empty() {
+                    let pairs = mem::take(&mut self.elem[ip][i]);
+                    self.elem[ip][i] = merge_pairs(&pairs, other_pairs);
+                }
+            }
+        }
+    }
+
     pub fn sort(&mut self) {
+        if self.size() == 0 {
+            return;
+        }
         let (min, max, width) = self.width();
-        let mut hist: Vec<usize> = vec![0; width];
-        let mut hist2: Vec<usize> = Vec::with_capacity(width);
-        let mut cumul: Vec<usize> = vec![0; width];
-        for chunk in &mut self.elem {
-            bucket_sort_pairs(
-                &mut chunk[0],
-                &mut hist,
-                &mut hist2,
-                &mut cumul,
-                min,
-                max,
-                width,
-            );
-            bucket_sort_pairs(
-                &mut chunk[1],
-                &mut hist,
-                &mut hist2,
-                &mut cumul,
-                min,
-                max,
-                width,
-            );
+        let buffers = || {
+            let hist: Vec<usize> = vec![0; width];
+            let hist2: Vec<usize> = Vec::with_capacity(width);
+            let cumul: Vec<usize> = vec![0; width];
+            (hist, hist2, cumul)
+        };
+        let sort_chunk = |(hist, hist2, cumul): &mut (Vec<usize>, Vec<usize>, Vec<usize>),
+                          chunk: &mut [Vec<[u64; 2]>; 2]| {
+            for pairs in chunk.iter_mut() {
+                bucket_sort_pairs(pairs, hist, hist2, cumul, min, max, width);
+            }
+        };
+        #[cfg(not(feature = "rayon"))]
+        {
+            let mut buffers = buffers();
+            for chunk in &mut self.elem {
+                sort_chunk(&mut buffers, chunk);
+            }
+        }
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            // the chunks are split in one group per thread, each group allocating its buffers once
+            let group = self.elem.len().div_ceil(rayon::current_num_threads());
+            self.elem.par_chunks_mut(group.max(1)).for_each(|chunks| {
+                let mut buffers = buffers();
+                for chunk in chunks {
+                    sort_chunk(&mut buffers, chunk);
+                }
+            });
         }
     }
 
@@ -85,6 +281,46 @@ impl TripleStore {
         /////////
     }
 
+    /// Replace every index found in `map` by its image,
+    /// merging the chunks of the replaced properties into those of their images
+    ///
+    /// # Post-condition
+    /// `self` must be sorted again
+    pub fn replace_all(&mut self, map: &HashMap<u64, u64>) {
+        for chunk in &mut self.elem {
+            for i in 0..=1 {
+                for pair in &mut chunk[i] {
+                    for index in pair.iter_mut() {
+                        if let Some(&new) = map.get(index) {
+                            *index = new;
+                        }
+                    }
+                }
+            }
+        }
+        for (&old, &new) in map {
+            if !NodeDictionary::is_property(old) || !NodeDictionary::is_property(new) {
+                continue;
+            }
+            let old = NodeDictionary::prop_idx_to_idx(old);
+            if old >= self.elem.len() {
+                continue;
+            }
+            let chunk = mem::take(&mut self.elem[old]);
+            let new = NodeDictionary::prop_idx_to_idx(new);
+            if new >= self.elem.len() {
+                self.elem.resize_with(new + 1, Default::default);
+            }
+            for [is, io] in chunk[0].iter() {
+                self.add_triple_raw(*is, new, *io);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elem.iter().all(|chunk| chunk[0].is_empty())
+    }
+
     pub fn size(&mut self) -> usize {
         let mut s = 0;
         for chunk in &self.elem {
@@ -158,6 +394,28 @@ pub fn bucket_sort_pairs(
     pairs.truncate(j);
 }
 
+/// Merge two sorted vectors of pairs, removing duplicates
+fn merge_pairs(pairs1: &[[u64; 2]], pairs2: &[[u64; 2]]) -> Vec<[u64; 2]> {
+    let mut merged = Vec::with_capacity(pairs1.len() + pairs2.len());
+    let (mut i, mut j) = (0, 0);
+    while i < pairs1.len() && j < pairs2.len() {
+        if pairs1[i] < pairs2[j] {
+            merged.push(pairs1[i]);
+            i += 1;
+        } else if pairs2[j] < pairs1[i] {
+            merged.push(pairs2[j]);
+            j += 1;
+        } else {
+            merged.push(pairs1[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+    merged.extend_from_slice(&pairs1[i..]);
+    merged.extend_from_slice(&pairs2[j..]);
+    merged
+}
+
 fn insertion_sort_slice(v: &mut [u64], from: usize, to: usize) {
     for i in from..to {
         let mut j = i;
@@ -43,3 +43,6 @@
 
 pub mod inferray;
 pub mod rules;
+
+#[cfg(test)]
+mod test_utils;
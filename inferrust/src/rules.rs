@@ -3,11 +3,41 @@
 mod _rules;
 pub use self::_rules::*;
 
+mod profiles;
+pub use self::profiles::*;
+
+mod dependencies;
+pub use self::dependencies::*;
+
 mod alpha_rules;
 pub use self::alpha_rules::*;
 
 mod beta_rules;
 pub use self::beta_rules::*;
 
+mod gamma_rules;
+pub use self::gamma_rules::*;
+
+mod delta_rules;
+pub use self::delta_rules::*;
+
 mod epsilon_rules;
 pub use self::epsilon_rules::*;
+
+mod rdfs_rules;
+pub use self::rdfs_rules::*;
+
+mod list_rules;
+pub use self::list_rules::*;
+
+mod restriction_rules;
+pub use self::restriction_rules::*;
+
+mod cardinality_rules;
+pub use self::cardinality_rules::*;
+
+mod chain_rules;
+pub use self::chain_rules::*;
+
+mod constraints;
+pub use self::constraints::*;
@@ -1,31 +1,146 @@
+use std::collections::HashMap;
+
 use crate::inferray::InfGraph;
+use crate::inferray::Justification;
+use crate::inferray::NodeDictionary;
 use crate::inferray::TripleStore;
 use crate::rules::*;
 
-/// A type alias  to unify all the rules of the reasoner
-pub type Rule = fn(&mut InfGraph) -> TripleStore;
-// pub trait Rule {
-//     // fn specialize(&mut self, graph: std::rc::Rc<&'static InfGraph>);
-//     fn fire(&mut self, graph: &mut InfGraph) -> TripleStore;
-// }
+/// A marker trait for the types which can be sent to the threads of the reasoner
+///
+/// It is `Send` when the `rayon` feature is enabled, and implemented by every type otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSend: Send {}
+
+#[cfg(feature = "rayon")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// A marker trait for the types which can be sent to the threads of the reasoner
+///
+/// It is `Send` when the `rayon` feature is enabled, and implemented by every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSend {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// A rule of the reasoner
+///
+/// Rules can be implemented outside of this crate, and added to a [`RuleSet`](trait.RuleSet.html).
+/// Rules must be `Send` when the `rayon` feature is enabled, as they are then fired concurrently
+/// (see [`MaybeSend`](trait.MaybeSend.html)).
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+///
+/// /// Every subject is a rdfs:Resource
+/// struct Resources;
+///
+/// impl Rule for Resources {
+///     fn name(&self) -> &str {
+///         "RESOURCES"
+///     }
+///
+///     fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+///         Some(vec![dictionary.rdftype])
+///     }
+///
+///     fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+///         let mut output = graph.new_output();
+///         let rdftype = graph.dictionary.rdftype as u64;
+///         for (idx, chunk) in delta.unwrap_or(&graph.dictionary.ts).elem.iter().enumerate() {
+///             let p = NodeDictionary::idx_to_prop_idx(idx);
+///             for pair in &chunk[0] {
+///                 output.add_inferred_triple(
+///                     [pair[0], rdftype, graph.dictionary.rdfsResource],
+///                     &[[pair[0], p, pair[1]]],
+///                 );
+///             }
+///         }
+///         output
+///     }
+/// }
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     :Bart :enfant :Lisa .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
+/// rules.push(Box::new(Resources));
+/// rules.fire_all(&mut graph);
+/// assert_eq!(graph.size(), 2);
+/// ```
+pub trait Rule: MaybeSend {
+    /// The name of this rule, e.g. `CAX-SCO`
+    fn name(&self) -> &str;
+
+    /// The predicates of the triples used by this rule, `None` meaning any predicate
+    ///
+    /// They may depend on the schema found in `dictionary.ts`.
+    /// A rule is only fired again by [`RuleSet::fire_all`](trait.RuleSet.html#tymethod.fire_all)
+    /// when triples with one of these predicates have been inferred.
+    fn input_predicates(&self, _dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        None
+    }
+
+    /// The predicates of the triples inferred by this rule, `None` meaning any predicate
+    fn output_predicates(&self, _dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        None
+    }
+
+    /// Apply this rule to `graph`, and return the inferred triples
+    ///
+    /// `delta`, if any, contains the triples added to `graph` since the last time this rule was fired
+    /// (it is sorted, and included in `graph`).
+    /// In that case, only the triples inferred from at least one triple of `delta` need to be returned.
+    /// Implementations may ignore `delta` and always apply the rule to the whole graph.
+    ///
+    /// The output should be created by [`InfGraph::new_output`](../inferray/struct.InfGraph.html#method.new_output),
+    /// and filled with [`TripleStore::add_inferred_triple`](../inferray/struct.TripleStore.html#method.add_inferred_triple),
+    /// so that the premises of the inferred triples are recorded when the provenance is enabled.
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore;
+}
 
 /// A set of Rule, which can be aplly on a InfGraph
 pub trait RuleSet {
-    fn new() -> Vec<Box<Rule>>;
+    /// Return the rules of the RDFS-Plus profile
+    fn new() -> Self;
+    /// Return the rules of the given profile
+    fn with_profile(profile: Profile) -> Self;
     // fn specialize(&mut self, graph: std::rc::Rc<&'static InfGraph>);
     fn fire_all(&mut self, graph: &mut InfGraph);
+    /// Apply the rules to `graph` until no new triple can be inferred,
+    /// assuming that `graph` was saturated before the triples of `added` were inserted
+    ///
+    /// `added` must be sorted, and included in `graph`.
+    fn fire_added(&mut self, graph: &mut InfGraph, added: TripleStore);
+    /// Retract from `graph` the consequences of the triples of `removed`,
+    /// assuming that `graph` was saturated before they were removed from the asserted triples
+    ///
+    /// This follows the Delete-and-Rederive (DRed) algorithm:
+    /// every triple inferred from a removed triple is deleted,
+    /// then the deleted triples which are asserted,
+    /// or can be inferred from the remaining triples, are inserted back.
+    /// The rules are only applied to the remaining triples sharing a term with the deleted ones.
+    ///
+    /// As merges of `owl:sameAs` cliques cannot be undone incrementally,
+    /// `graph` is saturated again from its asserted triples
+    /// if the retracted triples may have caused one.
+    ///
+    /// `removed` must be sorted, and included in `graph`.
+    fn fire_removed(&mut self, graph: &mut InfGraph, removed: TripleStore);
 }
 
-impl RuleSet for Vec<Box<Rule>> {
-    fn new() -> Vec<Box<Rule>> {
-        vec![
-            Box::new(CAX_SCO),
-            Box::new(CAX_EQC1),
-            Box::new(CAX_EQC2),
-            Box::new(SCM_EQC2),
-            Box::new(PRP_INV_1_2),
-            Box::new(PRP_EQP_1_2),
-        ]
+impl RuleSet for Vec<Box<dyn Rule>> {
+    fn new() -> Self {
+        Self::with_profile(Profile::RDFSPlus)
+    }
+    fn with_profile(profile: Profile) -> Self {
+        profile.rules()
     }
     // fn specialize(&mut self, graph: std::rc::Rc<&'static InfGraph>) {
     // for rule in self.iter() {
@@ -33,17 +148,204 @@ impl RuleSet for Vec<Box<Rule>> {
     // }
     // }
     fn fire_all(&mut self, graph: &mut InfGraph) {
-        let mut prev_size = 0;
-        let mut size = graph.size();
-        while prev_size != size {
-            prev_size = size;
-            let mut outputs = TripleStore::new();
-            for rule in self.iter_mut() {
-                outputs.add_all(rule(graph));
+        saturate(self, graph, None);
+    }
+    fn fire_added(&mut self, graph: &mut InfGraph, added: TripleStore) {
+        saturate(self, graph, Some(added));
+    }
+    fn fire_removed(&mut self, graph: &mut InfGraph, removed: TripleStore) {
+        // over-deletion of every triple having a derivation using a removed one
+        let mut deleted = removed.clone();
+        let mut delta = removed;
+        while !delta.is_empty() {
+            let triggered = DependencyGraph::new(self, &graph.dictionary).triggered(&delta);
+            let (mut outputs, _) = fire_rules(self, graph, Some(&delta), triggered);
+            outputs.sort();
+            delta = outputs
+                .intersection(&graph.dictionary.ts)
+                .difference(&deleted);
+            deleted.merge(&delta);
+        }
+        if graph.dictionary.unmerged.is_some() && affects_same_as(self, &graph.dictionary, &deleted)
+        {
+            graph.unmerge_same_as();
+            saturate(self, graph, None);
+            return;
+        }
+        graph.dictionary.ts = graph.dictionary.ts.difference(&deleted);
+        graph.unjustify(&deleted);
+        // re-derivation of the deleted triples which are asserted,
+        // or which can be inferred in one step from the remaining ones
+        let mut rederived = deleted.intersection(&graph.dictionary.asserted);
+        let triggered = DependencyGraph::from_outputs(self, &graph.dictionary).triggered(&deleted);
+        let premises = neighbourhood(&graph.dictionary.ts, &deleted);
+        let (mut outputs, justifications) = fire_rules(self, graph, Some(&premises), triggered);
+        outputs.sort();
+        rederived.merge(&outputs.intersection(&deleted));
+        graph.dictionary.ts.merge(&rederived);
+        graph.justify(&rederived, justifications);
+        saturate(self, graph, Some(rederived));
+    }
+}
+
+/// Return the triples of `store` using the subject of a triple of `deleted`,
+/// as subject, object, or predicate
+///
+/// As every term of the head of a rule is bound by its body,
+/// each derivation of a triple of `deleted` uses one of them:
+/// firing the rules with these triples as delta finds every deleted triple
+/// which can be inferred in one step from `store`,
+/// without applying them to the whole store.
+fn neighbourhood(store: &TripleStore, deleted: &TripleStore) -> TripleStore {
+    let mut subjects: Vec<u64> = deleted
+        .elem
+        .iter()
+        .flat_map(|chunk| chunk[0].iter().map(|pair| pair[0]))
+        .collect();
+    subjects.sort_unstable();
+    subjects.dedup();
+    let mut neighbourhood = TripleStore::new();
+    for (idx, chunk) in store.elem.iter().enumerate() {
+        if chunk[0].is_empty() {
+            continue;
+        }
+        let p = NodeDictionary::idx_to_prop_idx(idx);
+        if subjects.binary_search(&p).is_ok() {
+            for pair in &chunk[0] {
+                neighbourhood.add_triple([pair[0], p, pair[1]]);
             }
-            graph.dictionary.ts.add_all(outputs);
+            continue;
+        }
+        for &s in &subjects {
+            for pair in store.pairs_with(idx, 0, s) {
+                neighbourhood.add_triple([pair[0], p, pair[1]]);
+            }
+            for pair in store.pairs_with(idx, 1, s) {
+                neighbourhood.add_triple([pair[1], p, pair[0]]);
+            }
+        }
+    }
+    neighbourhood.sort();
+    neighbourhood
+}
+
+/// Return `true` if the triples of `deleted` may have caused a merge of `owl:sameAs` cliques,
+/// i.e. if they contain `owl:sameAs` triples, or trigger a rule which may infer some
+fn affects_same_as(
+    rules: &[Box<dyn Rule>],
+    dictionary: &NodeDictionary,
+    deleted: &TripleStore,
+) -> bool {
+    let same_as = dictionary.owlsameAs;
+    if !deleted
+        .pairs(NodeDictionary::prop_idx_to_idx(same_as as u64), 0)
+        .is_empty()
+    {
+        return true;
+    }
+    let triggered = DependencyGraph::new(rules, dictionary).triggered(deleted);
+    rules
+        .iter()
+        .zip(triggered)
+        .filter(|(_, triggered)| *triggered)
+        .any(|(rule, _)| match rule.output_predicates(dictionary) {
+            Some(predicates) => predicates.contains(&same_as),
+            None => true,
+        })
+}
+
+/// Apply the rules to `graph` until a fixpoint is reached,
+/// starting from the triples of `delta` if any, or from the whole graph
+fn saturate(rules: &mut [Box<dyn Rule>], graph: &mut InfGraph, mut delta: Option<TripleStore>) {
+    // semi-naive evaluation: after the first round,
+    // rules are only applied to the triples inferred by the previous one,
+    // and only if they read some of them
+    loop {
+        let triggered = match &delta {
+            Some(delta) => DependencyGraph::new(rules, &graph.dictionary).triggered(delta),
+            None => vec![true; rules.len()],
+        };
+        let (mut outputs, justifications) = fire_rules(rules, graph, delta.as_ref(), triggered);
+        outputs.sort();
+        let new = outputs.difference(&graph.dictionary.ts);
+        graph.dictionary.ts.merge(&new);
+        graph.justify(&new, justifications);
+        // merging owl:sameAs cliques rewrites the store, so the next round must be a full one
+        if graph.dictionary.merge_same_as() {
             graph.dictionary.ts.sort();
-            size = graph.size();
+            graph.canonicalize_provenance();
+            delta = None;
+        } else if new.is_empty() {
+            break;
+        } else {
+            delta = Some(new);
+        }
+    }
+}
+
+/// Fire the triggered rules, and gather the inferred triples,
+/// with their justifications if the provenance of `graph` is enabled
+#[cfg(not(feature = "rayon"))]
+fn fire_rules(
+    rules: &mut [Box<dyn Rule>],
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    triggered: Vec<bool>,
+) -> (TripleStore, HashMap<[u64; 3], Justification>) {
+    let mut outputs = TripleStore::new();
+    let mut justifications = HashMap::new();
+    for (rule, triggered) in rules.iter_mut().zip(triggered) {
+        if triggered {
+            let mut output = rule.fire(graph, delta);
+            if graph.provenance_enabled() {
+                merge_justifications(&mut justifications, output.take_justifications(rule.name()));
+            }
+            outputs.add_all(output);
         }
     }
+    (outputs, justifications)
+}
+
+/// Fire the triggered rules concurrently, and gather the inferred triples,
+/// with their justifications if the provenance of `graph` is enabled
+#[cfg(feature = "rayon")]
+fn fire_rules(
+    rules: &mut [Box<dyn Rule>],
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    triggered: Vec<bool>,
+) -> (TripleStore, HashMap<[u64; 3], Justification>) {
+    use rayon::prelude::*;
+
+    rules
+        .par_iter_mut()
+        .zip(triggered)
+        .filter(|(_, triggered)| *triggered)
+        .map(|(rule, _)| {
+            let mut output = rule.fire(graph, delta);
+            let justifications = if graph.provenance_enabled() {
+                output.take_justifications(rule.name())
+            } else {
+                HashMap::new()
+            };
+            (output, justifications)
+        })
+        .reduce(
+            || (TripleStore::new(), HashMap::new()),
+            |(mut outputs, mut justifications), (output, other)| {
+                outputs.add_all(output);
+                merge_justifications(&mut justifications, other);
+                (outputs, justifications)
+            },
+        )
+}
+
+/// Add the justifications of `other` to those of `justifications`, keeping the latter
+fn merge_justifications(
+    justifications: &mut HashMap<[u64; 3], Justification>,
+    other: HashMap<[u64; 3], Justification>,
+) {
+    for (triple, justification) in other {
+        justifications.entry(triple).or_insert(justification);
+    }
 }
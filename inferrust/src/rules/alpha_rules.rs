@@ -15,6 +15,8 @@
 //! <li>Inferred triple contains only s,p,o from the head</li>
 //! </ol>
 
+#![allow(non_camel_case_types)]
+
 use crate::inferray::InfGraph;
 use crate::inferray::NodeDictionary;
 use crate::inferray::TripleStore;
@@ -31,6 +33,7 @@ use sophia::term::StaticTerm;
 
 pub fn apply_alpha_rule(
     graph: &InfGraph,
+    delta: Option<&TripleStore>,
     id_1: u64,
     id_2: u64,
     id_s: u64,
@@ -39,16 +42,16 @@ pub fn apply_alpha_rule(
     id_c1: u64,
     id_c2: u64,
 ) -> TripleStore {
-    let property_1_pairs = graph.dictionary.ts.elem.get(id_1 as usize);
-    let property_2_pairs = graph.dictionary.ts.elem.get(id_2 as usize);
-    if property_1_pairs == None || property_2_pairs == None {
-        return TripleStore::new();
-    }
-    let property_1_pairs = property_1_pairs.unwrap();
-    let property_2_pairs = property_2_pairs.unwrap();
-    let mut output = TripleStore::new();
-    for property_1_pair in &property_1_pairs[0] {
-        for property_2_pair in &property_2_pairs[0] {
+    let ts = &graph.dictionary.ts;
+    let premise = |id: u64, pair: &[u64; 2]| {
+        [
+            pair[0],
+            NodeDictionary::idx_to_prop_idx(id as usize),
+            pair[1],
+        ]
+    };
+    let infer =
+        |property_1_pair: &[u64; 2], property_2_pair: &[u64; 2], output: &mut TripleStore| {
             let index = |i| match i {
                 0 => property_1_pair[0],
                 1 => id_1,
@@ -58,32 +61,400 @@ pub fn apply_alpha_rule(
                 5 => property_2_pair[1],
                 _ => 0,
             };
-            if index(id_c2) == index(id_c1) {
-                output.add_triple([
+            output.add_inferred_triple(
+                [
                     index(id_s),
                     NodeDictionary::idx_to_prop_idx(index(id_p) as usize),
                     index(id_o),
-                ]);
+                ],
+                &[
+                    premise(id_1, property_1_pair),
+                    premise(id_2, property_2_pair),
+                ],
+            );
+        };
+    // position of the join variable in the pairs of each property,
+    // which is also the order of the vector to search
+    let key_1 = if id_c1 == 0 { 0 } else { 1 };
+    let key_2 = if id_c2 == 3 { 0 } else { 1 };
+    let restore = |pair: &[u64; 2], key| if key == 0 { *pair } else { [pair[1], pair[0]] };
+    let mut output = graph.new_output();
+    // with a delta, join (delta x graph) and (graph x delta)
+    let (outer, inner) = match delta {
+        Some(delta) => (delta, ts),
+        None => (ts, ts),
+    };
+    for property_1_pair in outer.pairs(id_1 as usize, 0) {
+        for property_2_pair in inner.pairs_with(id_2 as usize, key_2, property_1_pair[key_1]) {
+            infer(
+                property_1_pair,
+                &restore(property_2_pair, key_2),
+                &mut output,
+            );
+        }
+    }
+    if let Some(delta) = delta {
+        for property_2_pair in delta.pairs(id_2 as usize, 0) {
+            for property_1_pair in ts.pairs_with(id_1 as usize, key_1, property_2_pair[key_2]) {
+                infer(
+                    &restore(property_1_pair, key_1),
+                    property_2_pair,
+                    &mut output,
+                );
             }
         }
     }
     output
 }
 
-pub fn CAX_SCO(graph: &mut InfGraph) -> TripleStore {
-    let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64) as u64;
-    let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64) as u64;
-    apply_alpha_rule(graph, id_1, id_2, 3, 4, 2, 0, 5)
+pub struct CAX_SCO;
+
+impl Rule for CAX_SCO {
+    fn name(&self) -> &str {
+        "CAX-SCO"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf, dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64) as u64;
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 3, 4, 2, 0, 5)
+    }
 }
 
-pub fn CAX_EQC1(graph: &mut InfGraph) -> TripleStore {
-    let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentClass as u64) as u64;
-    let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64) as u64;
-    apply_alpha_rule(graph, id_1, id_2, 3, 4, 2, 0, 5)
+pub struct CAX_EQC1;
+
+impl Rule for CAX_EQC1 {
+    fn name(&self) -> &str {
+        "CAX-EQC1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlequivalentClass, dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 =
+            NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentClass as u64) as u64;
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 3, 4, 2, 0, 5)
+    }
 }
 
-pub fn CAX_EQC2(graph: &mut InfGraph) -> TripleStore {
-    let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentClass as u64) as u64;
-    let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64) as u64;
-    apply_alpha_rule(graph, id_1, id_2, 3, 4, 0, 2, 5)
+pub struct CAX_EQC2;
+
+impl Rule for CAX_EQC2 {
+    fn name(&self) -> &str {
+        "CAX-EQC2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlequivalentClass, dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 =
+            NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentClass as u64) as u64;
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 3, 4, 0, 2, 5)
+    }
+}
+
+/// The SCM-DOM1 rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdfs:domain c1
+/// - c1 rdfs:subClassOf c2
+/// Head:
+/// - p rdfs:domain c2
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+/// use sophia::term::RcTerm;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+///
+///     :hasPet rdfs:domain :human .
+///     :human rdfs:subClassOf :mammal .
+///     :mammal rdfs:subClassOf :animal .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::new();
+/// rules.fire_all(&mut graph);
+///
+/// let index = |iri| {
+///     let term = RcTerm::new_iri(iri).unwrap();
+///     graph.dictionary.get_index(&term).unwrap()
+/// };
+/// let domain = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsdomain as u64);
+/// let domains = &graph.dictionary.ts.elem[domain][0];
+/// let has_pet = index("http://example.org/hasPet");
+/// assert!(domains.contains(&[has_pet, index("http://example.org/mammal")]));
+/// assert!(domains.contains(&[has_pet, index("http://example.org/animal")]));
+/// ```
+pub struct SCM_DOM1;
+
+impl Rule for SCM_DOM1 {
+    fn name(&self) -> &str {
+        "SCM-DOM1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsdomain, dictionary.rdfssubClassOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsdomain])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsdomain as u64) as u64;
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 0, 1, 5, 2, 3)
+    }
+}
+
+/// The SCM-DOM2 rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p2 rdfs:domain c
+/// - p1 rdfs:subPropertyOf p2
+/// Head:
+/// - p1 rdfs:domain c
+pub struct SCM_DOM2;
+
+impl Rule for SCM_DOM2 {
+    fn name(&self) -> &str {
+        "SCM-DOM2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsdomain, dictionary.rdfssubPropertyOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsdomain])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsdomain as u64) as u64;
+        let id_2 =
+            NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubPropertyOf as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 3, 1, 2, 0, 5)
+    }
+}
+
+/// The SCM-RNG1 rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdfs:range c1
+/// - c1 rdfs:subClassOf c2
+/// Head:
+/// - p rdfs:range c2
+pub struct SCM_RNG1;
+
+impl Rule for SCM_RNG1 {
+    fn name(&self) -> &str {
+        "SCM-RNG1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsrange, dictionary.rdfssubClassOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsrange])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsrange as u64) as u64;
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 0, 1, 5, 2, 3)
+    }
+}
+
+/// The SCM-RNG2 rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p2 rdfs:range c
+/// - p1 rdfs:subPropertyOf p2
+/// Head:
+/// - p1 rdfs:range c
+pub struct SCM_RNG2;
+
+impl Rule for SCM_RNG2 {
+    fn name(&self) -> &str {
+        "SCM-RNG2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsrange, dictionary.rdfssubPropertyOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfsrange])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsrange as u64) as u64;
+        let id_2 =
+            NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubPropertyOf as u64) as u64;
+        apply_alpha_rule(graph, delta, id_1, id_2, 3, 1, 2, 0, 5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn cax_sco() {
+        let graph = saturate(
+            ":Bart a :human . :human rdfs:subClassOf :mammal . :Lisa a :robot .",
+            vec![Box::new(CAX_SCO)],
+        );
+        assert!(has(&graph, ":Bart", "rdf:type", ":mammal"));
+        assert!(!has(&graph, ":Lisa", "rdf:type", ":mammal"));
+    }
+
+    #[test]
+    fn cax_eqc() {
+        let rep = ":Bart a :human . :human owl:equivalentClass :person . :Lisa a :person .";
+        let graph = saturate(rep, vec![Box::new(CAX_EQC1)]);
+        assert!(has(&graph, ":Bart", "rdf:type", ":person"));
+        assert!(!has(&graph, ":Lisa", "rdf:type", ":human"));
+        let graph = saturate(rep, vec![Box::new(CAX_EQC2)]);
+        assert!(has(&graph, ":Lisa", "rdf:type", ":human"));
+        assert!(!has(&graph, ":Bart", "rdf:type", ":person"));
+    }
+
+    #[test]
+    fn scm_dom1() {
+        let graph = saturate_with_provenance(
+            r#"
+            :hasPet rdfs:domain :human .
+            :human rdfs:subClassOf :mammal .
+            :mammal rdfs:subClassOf :animal .
+            :hasToy rdfs:domain :child .
+            :human rdfs:subClassOf :child .
+            "#,
+            vec![Box::new(SCM_DOM1)],
+        );
+        assert!(has(&graph, ":hasPet", "rdfs:domain", ":animal"));
+        assert!(!has(&graph, ":hasToy", "rdfs:domain", ":human"));
+        // the domain :animal is inferred from the inferred domain :mammal
+        let derivation = graph
+            .explain(&iri(":hasPet"), &iri("rdfs:domain"), &iri(":animal"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("SCM-DOM1"));
+        assert_eq!(derivation.premises.len(), 2);
+        assert_eq!(derivation.premises[0].rule, Some("SCM-DOM1"));
+        assert_eq!(derivation.premises[1].rule, None);
+    }
+
+    #[test]
+    fn scm_dom2_delta() {
+        let graph = graph(
+            r#"
+            :hasPet rdfs:domain :human .
+            :hasDog rdfs:subPropertyOf :hasPet .
+            :hasCat rdfs:subPropertyOf :hasPet .
+            :hasPet rdfs:subPropertyOf :owns .
+            "#,
+        );
+        let output = fire(&graph, &mut SCM_DOM2, None);
+        assert!(contains(
+            &graph,
+            &output,
+            ":hasDog",
+            "rdfs:domain",
+            ":human"
+        ));
+        assert!(contains(
+            &graph,
+            &output,
+            ":hasCat",
+            "rdfs:domain",
+            ":human"
+        ));
+        assert!(!contains(&graph, &output, ":owns", "rdfs:domain", ":human"));
+        // only the joins involving a triple of the delta are computed
+        let delta = [[":hasDog", "rdfs:subPropertyOf", ":hasPet"]];
+        let output = fire(&graph, &mut SCM_DOM2, Some(&delta));
+        assert!(contains(
+            &graph,
+            &output,
+            ":hasDog",
+            "rdfs:domain",
+            ":human"
+        ));
+        assert!(!contains(
+            &graph,
+            &output,
+            ":hasCat",
+            "rdfs:domain",
+            ":human"
+        ));
+    }
+
+    #[test]
+    fn scm_rng1() {
+        let graph = graph(
+            r#"
+            :hasPet rdfs:range :pet .
+            :pet rdfs:subClassOf :animal .
+            :dog rdfs:subClassOf :pet .
+            "#,
+        );
+        let output = fire(&graph, &mut SCM_RNG1, None);
+        assert!(contains(
+            &graph,
+            &output,
+            ":hasPet",
+            "rdfs:range",
+            ":animal"
+        ));
+        assert!(!contains(&graph, &output, ":hasPet", "rdfs:range", ":dog"));
+        // a subclass which is not the range of any property infers nothing
+        let delta = [[":dog", "rdfs:subClassOf", ":pet"]];
+        assert!(fire(&graph, &mut SCM_RNG1, Some(&delta)).is_empty());
+        let predicates = SCM_RNG1.input_predicates(&graph.dictionary).unwrap();
+        assert!(!predicates.contains(&graph.dictionary.rdftype));
+    }
+
+    #[test]
+    fn scm_rng2() {
+        let graph = saturate(
+            r#"
+            :hasPet rdfs:range :pet .
+            :hasDog rdfs:subPropertyOf :hasPet .
+            :hasPet rdfs:subPropertyOf :owns .
+            :hasBeagle rdfs:subPropertyOf :hasDog .
+            "#,
+            vec![Box::new(SCM_RNG2)],
+        );
+        assert!(has(&graph, ":hasBeagle", "rdfs:range", ":pet"));
+        assert!(!has(&graph, ":owns", "rdfs:range", ":pet"));
+    }
 }
@@ -4,6 +4,7 @@
 //! <li>SCM-EQC2</li>
 //! <li>SCM-SPO</li>
 //! <li>SCM-EQP2</li>
+//! <li>PRP-TRP</li>
 //! </ul>
 //!
 //! All these rules have the following properties :
@@ -11,35 +12,246 @@
 //! <li>same predicate in both parts</li>
 //! </ol>
 
+#![allow(non_camel_case_types)]
+
 use crate::inferray::InfGraph;
 use crate::inferray::NodeDictionary;
 use crate::inferray::TripleStore;
 use crate::rules::Rule;
+use crate::rules::{instances_of, with_properties};
 
 use sophia::ns::*;
 use sophia::term::StaticTerm;
 
 /// General method to apply a rule of the beta class, given the indexes of the rule property and of the inferred one
-pub fn apply_beta_rule(graph: &InfGraph, rule_p: usize, infer_p: usize) -> TripleStore {
-    let pairs = graph.dictionary.ts.elem.get(rule_p);
-    if pairs == None {
-        return TripleStore::new();
-    }
+///
+/// If `delta` is given, only the pairs it contains are looked up in the graph.
+pub fn apply_beta_rule(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    rule_p: usize,
+    infer_p: usize,
+) -> TripleStore {
+    let ts = &graph.dictionary.ts;
     let infer_p = NodeDictionary::idx_to_prop_idx(infer_p);
-    let pairs1 = pairs.unwrap();
-    let pairs2 = pairs.unwrap();
-    let mut output = TripleStore::new();
-    for pair1 in &pairs1[0] {
-        for pair2 in &pairs2[0] {
-            if pair1[0] == pair2[1] && pair1[1] == pair2[0] {
-                output.add_triple([pair1[0], infer_p, pair1[1]]);
-                output.add_triple([pair2[0], infer_p, pair2[1]]);
-            }
+    let rule_prop = NodeDictionary::idx_to_prop_idx(rule_p);
+    let mut output = graph.new_output();
+    for pair in delta.unwrap_or(ts).pairs(rule_p, 0) {
+        if ts.contains_pair(rule_p, [pair[1], pair[0]]) {
+            let premises = [[pair[0], rule_prop, pair[1]], [pair[1], rule_prop, pair[0]]];
+            output.add_inferred_triple([pair[0], infer_p, pair[1]], &premises);
+            output.add_inferred_triple([pair[1], infer_p, pair[0]], &premises);
         }
     }
     output
 }
 
+/// General method to apply a transitive rule of the beta class, given the index of the rule property
+///
+/// The inferred triples use the same property as the rule.
+/// If `delta` is given, the closure is only computed when it contains pairs of this property.
+pub fn apply_transitive_rule(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    rule_p: usize,
+) -> TripleStore {
+    let mut output = graph.new_output();
+    if let Some(delta) = delta {
+        if delta.pairs(rule_p, 0).is_empty() {
+            return output;
+        }
+    }
+    let infer_p = NodeDictionary::idx_to_prop_idx(rule_p);
+    let pairs = graph.dictionary.ts.pairs(rule_p, 0);
+    if output.records_premises() {
+        for ([s, o], via) in justified_closure(pairs) {
+            let premises = [[s, infer_p, via], [via, infer_p, o]];
+            output.add_inferred_triple([s, infer_p, o], if via == s { &[] } else { &premises });
+        }
+    } else {
+        for [s, o] in transitive_closure(pairs) {
+            output.add_triple([s, infer_p, o]);
+        }
+    }
+    output
+}
+
+/// Compute the transitive closure of a sorted binary relation,
+/// with a justification for each pair `[x, z]`:
+/// a term `y` such that `[x, y]` is a pair of the closure, and `[y, z]` a pair of the relation
+/// (`y` being `x` for the pairs of the relation)
+///
+/// The closure is computed by a breadth-first search from each subject,
+/// so that the justifications never loop, which is slower than
+/// [`transitive_closure`](fn.transitive_closure.html).
+fn justified_closure(pairs: &[[u64; 2]]) -> Vec<([u64; 2], u64)> {
+    let successors = |node: u64| {
+        let start = pairs.partition_point(|pair| pair[0] < node);
+        let end = start + pairs[start..].partition_point(|pair| pair[0] == node);
+        &pairs[start..end]
+    };
+    let mut closure = Vec::new();
+    let mut first = 0;
+    while first < pairs.len() {
+        let source = pairs[first][0];
+        let mut visited = std::collections::HashSet::new();
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(source);
+        while let Some(via) = queue.pop_front() {
+            for pair in successors(via) {
+                if visited.insert(pair[1]) {
+                    closure.push(([source, pair[1]], via));
+                    queue.push_back(pair[1]);
+                }
+            }
+        }
+        first += successors(source).len();
+    }
+    closure
+}
+
+/// Compute the transitive closure of a binary relation
+///
+/// Following Inferray, the graph of the relation is first condensed into its
+/// strongly connected components (using an iterative version of Tarjan's algorithm),
+/// then the successors of each component are computed once,
+/// in reverse topological order, by merging the successors of its direct successors.
+/// The input pairs need not be sorted; the output contains the input pairs.
+pub fn transitive_closure(pairs: &[[u64; 2]]) -> Vec<[u64; 2]> {
+    // Number the nodes densely
+    let mut nodes: Vec<u64> = pairs.iter().flat_map(|pair| pair.iter().cloned()).collect();
+    nodes.sort_unstable();
+    nodes.dedup();
+    let n = nodes.len();
+    let node_idx = |node: u64| nodes.binary_search(&node).expect("Unknown node");
+
+    // Successor lists, stored contiguously: succ[start[v]..start[v + 1]]
+    let mut start = vec![0; n + 1];
+    for pair in pairs {
+        start[node_idx(pair[0]) + 1] += 1;
+    }
+    for v in 0..n {
+        start[v + 1] += start[v];
+    }
+    let mut succ = vec![0; pairs.len()];
+    let mut fill = start.clone();
+    for pair in pairs {
+        let v = node_idx(pair[0]);
+        succ[fill[v]] = node_idx(pair[1]);
+        fill[v] += 1;
+    }
+
+    // Tarjan's algorithm, which yields the components in reverse topological order
+    const UNVISITED: usize = usize::max_value();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut component = vec![0; n];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut stack = Vec::new();
+    let mut counter = 0;
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        let mut calls = vec![(root, start[root])];
+        index[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(&(v, next)) = calls.last() {
+            if next < start[v + 1] {
+                calls.last_mut().unwrap().1 += 1;
+                let w = succ[next];
+                if index[w] == UNVISITED {
+                    index[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, start[w]));
+                } else if on_stack[w] && index[w] < low[v] {
+                    low[v] = index[w];
+                }
+            } else {
+                calls.pop();
+                if let Some(&(u, _)) = calls.last() {
+                    if low[v] < low[u] {
+                        low[u] = low[v];
+                    }
+                }
+                if low[v] == index[v] {
+                    let mut members = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component[w] = components.len();
+                        members.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    components.push(members);
+                }
+            }
+        }
+    }
+
+    // Successors of each component, sorted and without duplicates
+    let mut reachable: Vec<Vec<usize>> = Vec::with_capacity(components.len());
+    for (c, members) in components.iter().enumerate() {
+        let mut r = Vec::new();
+        for &v in members {
+            for &w in &succ[start[v]..start[v + 1]] {
+                r.push(w);
+                if component[w] != c {
+                    r.extend_from_slice(&reachable[component[w]]);
+                }
+            }
+        }
+        r.sort_unstable();
+        r.dedup();
+        reachable.push(r);
+    }
+
+    let mut closure = Vec::new();
+    for v in 0..n {
+        for &w in &reachable[component[v]] {
+            closure.push([nodes[v], nodes[w]]);
+        }
+    }
+    closure
+}
+
+/// The SCM-SCO rule from the RDFS+ ruleset
+///
+/// Body:
+/// - c1 rdfs:subClassOf c2
+/// - c2 rdfs:subClassOf c3
+/// Head:
+/// - c1 rdfs:subClassOf c3
+pub struct SCM_SCO;
+
+impl Rule for SCM_SCO {
+    fn name(&self) -> &str {
+        "SCM-SCO"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64);
+        apply_transitive_rule(graph, delta, id)
+    }
+}
+
 /// The SCM-EQC2 rule from the RDFS+ ruleset
 ///
 /// Body:
@@ -48,8 +260,307 @@ pub fn apply_beta_rule(graph: &InfGraph, rule_p: usize, infer_p: usize) -> Tripl
 /// Head:
 /// - c1 owl:equivalentClass c2
 /// - c2 owl:equivalentClass c1
-pub fn SCM_EQC2(graph: &mut InfGraph) -> TripleStore {
-    let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64);
-    let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentClass as u64);
-    apply_beta_rule(graph, id_1, id_2)
+pub struct SCM_EQC2;
+
+impl Rule for SCM_EQC2 {
+    fn name(&self) -> &str {
+        "SCM-EQC2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlequivalentClass])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubClassOf as u64);
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentClass as u64);
+        apply_beta_rule(graph, delta, id_1, id_2)
+    }
+}
+
+/// The SCM-SPO rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p1 rdfs:subPropertyOf p2
+/// - p2 rdfs:subPropertyOf p3
+/// Head:
+/// - p1 rdfs:subPropertyOf p3
+pub struct SCM_SPO;
+
+impl Rule for SCM_SPO {
+    fn name(&self) -> &str {
+        "SCM-SPO"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubPropertyOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubPropertyOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubPropertyOf as u64);
+        apply_transitive_rule(graph, delta, id)
+    }
+}
+
+/// The SCM-EQP2 rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p1 rdfs:subPropertyOf p2
+/// - p2 rdfs:subPropertyOf p1
+/// Head:
+/// - p1 owl:equivalentProperty p2
+/// - p2 owl:equivalentProperty p1
+pub struct SCM_EQP2;
+
+impl Rule for SCM_EQP2 {
+    fn name(&self) -> &str {
+        "SCM-EQP2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubPropertyOf])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlequivalentProperty])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id_1 = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubPropertyOf as u64);
+        let id_2 = NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentProperty as u64);
+        apply_beta_rule(graph, delta, id_1, id_2)
+    }
+}
+
+/// The PRP-TRP rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdf:type owl:TransitiveProperty
+/// - x p y
+/// - y p z
+/// Head:
+/// - x p z
+///
+/// The closure of each transitive property is computed at once,
+/// rather than one step per iteration of the rule set.
+pub struct PRP_TRP;
+
+impl Rule for PRP_TRP {
+    fn name(&self) -> &str {
+        "PRP-TRP"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let typed = instances_of(dictionary, dictionary.owltransitiveProperty as u64);
+        Some(with_properties(vec![dictionary.rdftype], typed))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let typed = instances_of(dictionary, dictionary.owltransitiveProperty as u64);
+        Some(with_properties(vec![], typed))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let mut output = graph.new_output();
+        let id = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64);
+        let transitive = graph.dictionary.owltransitiveProperty as u64;
+        for pair in graph.dictionary.ts.pairs_with(id, 1, transitive) {
+            if !NodeDictionary::is_property(pair[1]) {
+                continue;
+            }
+            let p = NodeDictionary::prop_idx_to_idx(pair[1]);
+            // a newly transitive property must be closed even if it gained no pair
+            let new_type = match delta {
+                Some(delta) => delta.contains_pair(id, [pair[1], transitive]),
+                None => true,
+            };
+            output.add_all(apply_transitive_rule(
+                graph,
+                if new_type { None } else { delta },
+                p,
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn scm_sco() {
+        let graph = saturate_with_provenance(
+            r#"
+            :human rdfs:subClassOf :mammal .
+            :mammal rdfs:subClassOf :vertebrate .
+            :vertebrate rdfs:subClassOf :animal .
+            :robot rdfs:subClassOf :machine .
+            "#,
+            vec![Box::new(SCM_SCO)],
+        );
+        assert!(has(&graph, ":human", "rdfs:subClassOf", ":animal"));
+        assert!(!has(&graph, ":robot", "rdfs:subClassOf", ":animal"));
+        // the justification goes through the last intermediate class
+        let derivation = graph
+            .explain(&iri(":human"), &iri("rdfs:subClassOf"), &iri(":animal"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("SCM-SCO"));
+        assert_eq!(derivation.premises[0].rule, Some("SCM-SCO"));
+        assert_eq!(derivation.premises[1].rule, None);
+        assert_eq!(
+            inferred_by(&graph, ":human", "rdfs:subClassOf", ":mammal"),
+            None
+        );
+    }
+
+    #[test]
+    fn scm_sco_delta() {
+        let graph = graph(
+            ":Bart a :human . :human rdfs:subClassOf :mammal . :mammal rdfs:subClassOf :animal .",
+        );
+        // a delta without any subclass does not trigger the closure
+        let delta = [[":Bart", "rdf:type", ":human"]];
+        assert!(fire(&graph, &mut SCM_SCO, Some(&delta)).is_empty());
+        let delta = [[":human", "rdfs:subClassOf", ":mammal"]];
+        let output = fire(&graph, &mut SCM_SCO, Some(&delta));
+        assert!(contains(
+            &graph,
+            &output,
+            ":human",
+            "rdfs:subClassOf",
+            ":animal"
+        ));
+    }
+
+    #[test]
+    fn transitive_closure_cycle() {
+        let mut closure = transitive_closure(&[[3, 1], [1, 2], [2, 1], [4, 4]]);
+        closure.sort_unstable();
+        assert_eq!(
+            closure,
+            vec![[1, 1], [1, 2], [2, 1], [2, 2], [3, 1], [3, 2], [4, 4]]
+        );
+        assert!(transitive_closure(&[]).is_empty());
+    }
+
+    #[test]
+    fn scm_eqc2() {
+        let graph = graph(
+            r#"
+            :human rdfs:subClassOf :person .
+            :person rdfs:subClassOf :human .
+            :human rdfs:subClassOf :animal .
+            "#,
+        );
+        // one direction in the delta is enough to infer both equivalences
+        let delta = [[":person", "rdfs:subClassOf", ":human"]];
+        let output = fire(&graph, &mut SCM_EQC2, Some(&delta));
+        assert!(contains(
+            &graph,
+            &output,
+            ":human",
+            "owl:equivalentClass",
+            ":person"
+        ));
+        assert!(contains(
+            &graph,
+            &output,
+            ":person",
+            "owl:equivalentClass",
+            ":human"
+        ));
+        let delta = [[":human", "rdfs:subClassOf", ":animal"]];
+        assert!(fire(&graph, &mut SCM_EQC2, Some(&delta)).is_empty());
+    }
+
+    #[test]
+    fn scm_spo() {
+        let graph = saturate(
+            r#"
+            :hasDog rdfs:subPropertyOf :hasPet .
+            :hasPet rdfs:subPropertyOf :owns .
+            :knows rdfs:subPropertyOf :meets .
+            "#,
+            vec![Box::new(SCM_SPO)],
+        );
+        assert!(has(&graph, ":hasDog", "rdfs:subPropertyOf", ":owns"));
+        assert!(!has(&graph, ":knows", "rdfs:subPropertyOf", ":owns"));
+        let predicates = SCM_SPO.output_predicates(&graph.dictionary).unwrap();
+        assert_eq!(predicates, vec![graph.dictionary.rdfssubPropertyOf]);
+    }
+
+    #[test]
+    fn scm_eqp2() {
+        let graph = saturate_with_provenance(
+            r#"
+            :hasPet rdfs:subPropertyOf :owns .
+            :owns rdfs:subPropertyOf :hasPet .
+            :hasDog rdfs:subPropertyOf :hasPet .
+            "#,
+            vec![Box::new(SCM_EQP2)],
+        );
+        assert!(has(&graph, ":owns", "owl:equivalentProperty", ":hasPet"));
+        assert!(!has(&graph, ":hasDog", "owl:equivalentProperty", ":hasPet"));
+        let derivation = graph
+            .explain(
+                &iri(":hasPet"),
+                &iri("owl:equivalentProperty"),
+                &iri(":owns"),
+            )
+            .unwrap();
+        assert_eq!(derivation.rule, Some("SCM-EQP2"));
+        assert_eq!(derivation.premises.len(), 2);
+    }
+
+    #[test]
+    fn prp_trp() {
+        let graph = saturate_with_provenance(
+            r#"
+            :ancestor a owl:TransitiveProperty .
+            :Bart :ancestor :Homer . :Homer :ancestor :Abe . :Abe :ancestor :Orville .
+            :Bart :knows :Milhouse . :Milhouse :knows :Luann .
+            "#,
+            vec![Box::new(PRP_TRP)],
+        );
+        assert!(has(&graph, ":Bart", ":ancestor", ":Orville"));
+        assert!(!has(&graph, ":Bart", ":knows", ":Luann"));
+        let derivation = graph
+            .explain(&iri(":Bart"), &iri(":ancestor"), &iri(":Orville"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("PRP-TRP"));
+        assert_eq!(derivation.premises[0].rule, Some("PRP-TRP"));
+        assert_eq!(derivation.premises[1].rule, None);
+    }
+
+    #[test]
+    fn prp_trp_delta() {
+        let graph = graph(
+            r#"
+            :ancestor a owl:TransitiveProperty .
+            :Bart :ancestor :Homer . :Homer :ancestor :Abe .
+            :Bart :knows :Milhouse . :Milhouse :knows :Luann .
+            "#,
+        );
+        // no pair of a transitive property in the delta
+        let delta = [[":Bart", ":knows", ":Milhouse"]];
+        assert!(fire(&graph, &mut PRP_TRP, Some(&delta)).is_empty());
+        let delta = [[":Homer", ":ancestor", ":Abe"]];
+        let output = fire(&graph, &mut PRP_TRP, Some(&delta));
+        assert!(contains(&graph, &output, ":Bart", ":ancestor", ":Abe"));
+        // a newly transitive property is closed without any new pair
+        let delta = [[":ancestor", "rdf:type", "owl:TransitiveProperty"]];
+        let output = fire(&graph, &mut PRP_TRP, Some(&delta));
+        assert!(contains(&graph, &output, ":Bart", ":ancestor", ":Abe"));
+        assert!(!contains(&graph, &output, ":Bart", ":knows", ":Luann"));
+    }
 }
@@ -0,0 +1,697 @@
+//! The cardinality class groups the following rules :
+//! <ul>
+//! <li>CLS-MAXC2</li>
+//! <li>CLS-MAXQC3</li>
+//! <li>CLS-MAXQC4</li>
+//! </ul>
+//! and the following constraints :
+//! <ul>
+//! <li>CLS-MAXC1</li>
+//! <li>CLS-MAXQC1</li>
+//! <li>CLS-MAXQC2</li>
+//! </ul>
+//!
+//! All of them use cardinality restrictions `x card_p n`, `x owl:onProperty p`
+//! (and `x owl:onClass c` for qualified ones),
+//! where `card_p` is `owl:maxCardinality` or `owl:maxQualifiedCardinality`,
+//! and `n` is a literal of datatype `xsd:nonNegativeInteger`, whose value is 0 or 1.
+//! A `delta` containing a triple of a restriction
+//! triggers a full application of the rule.
+//!
+//! The rules of cardinality 1 never infer `owl:sameAs` between literals,
+//! which cannot be merged: two literals of distinct values are reported
+//! by the constraint of the same name instead
+//! (see `NodeDictionary::distinct_literals` for the values which are compared).
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::{link_same_as, touches, with_properties};
+use crate::rules::{Constraint, Inconsistency, Rule};
+
+/// A cardinality restriction of class `class` on property `property`,
+/// qualified by class `on_class` if any
+struct CardinalityRestriction {
+    class: u64,
+    property: u64,
+    on_class: Option<u64>,
+    /// The triples defining this restriction
+    triples: Vec<[u64; 3]>,
+}
+
+impl CardinalityRestriction {
+    /// Return `true` if the values of this restriction must have a given type,
+    /// i.e. if it is qualified by a class other than `owl:Thing`
+    fn checks_type(&self, graph: &InfGraph) -> bool {
+        match self.on_class {
+            Some(c) => c != graph.dictionary.owlthing as u64,
+            None => false,
+        }
+    }
+
+    /// Return the values of this restriction for individual `u`, with the premises of each one
+    fn values(&self, graph: &InfGraph, u: u64) -> Vec<(u64, Vec<[u64; 3]>)> {
+        let ts = &graph.dictionary.ts;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+        let p = self.property;
+        ts.pairs_with(NodeDictionary::prop_idx_to_idx(p), 0, u)
+            .iter()
+            .filter_map(|pair| {
+                let y = pair[1];
+                if !self.checks_type(graph) {
+                    return Some((y, vec![[u, p, y]]));
+                }
+                let c = self.on_class.unwrap();
+                if ts.contains_pair(type_idx, [y, c]) {
+                    Some((y, vec![[u, p, y], [y, rdftype, c]]))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Return the cardinality restrictions of property `card_p` whose cardinality is `cardinality`
+///
+/// If `qualified`, only the restrictions having an `owl:onClass` are returned.
+/// Restrictions whose property is not used as a predicate are ignored.
+fn cardinality_restrictions(
+    graph: &InfGraph,
+    card_p: u32,
+    cardinality: u64,
+    qualified: bool,
+) -> Vec<CardinalityRestriction> {
+    let dictionary = &graph.dictionary;
+    let ts = &dictionary.ts;
+    let on_property = dictionary.onProperty as u64;
+    let on_class = dictionary.onClass as u64;
+    let mut restrictions = Vec::new();
+    for pair in ts.pairs(NodeDictionary::prop_idx_to_idx(card_p as u64), 0) {
+        let [x, n] = *pair;
+        if dictionary.non_negative_integer(n) != Some(cardinality) {
+            continue;
+        }
+        for property_pair in ts.pairs_with(NodeDictionary::prop_idx_to_idx(on_property), 0, x) {
+            let p = property_pair[1];
+            if !NodeDictionary::is_property(p) {
+                continue;
+            }
+            let triples = vec![[x, card_p as u64, n], [x, on_property, p]];
+            if !qualified {
+                restrictions.push(CardinalityRestriction {
+                    class: x,
+                    property: p,
+                    on_class: None,
+                    triples,
+                });
+                continue;
+            }
+            for class_pair in ts.pairs_with(NodeDictionary::prop_idx_to_idx(on_class), 0, x) {
+                let mut triples = triples.clone();
+                triples.push([x, on_class, class_pair[1]]);
+                restrictions.push(CardinalityRestriction {
+                    class: x,
+                    property: p,
+                    on_class: Some(class_pair[1]),
+                    triples,
+                });
+            }
+        }
+    }
+    restrictions
+}
+
+/// Return the predicates defining the restrictions of property `card_p`
+fn restriction_predicates(dictionary: &NodeDictionary, card_p: u32) -> Vec<u32> {
+    vec![card_p, dictionary.onProperty, dictionary.onClass]
+}
+
+/// Return the predicates read by a rule on the restrictions of property `card_p`
+fn cardinality_inputs(dictionary: &NodeDictionary, card_p: u32) -> Vec<u32> {
+    let on_property = NodeDictionary::prop_idx_to_idx(dictionary.onProperty as u64);
+    let mut predicates = restriction_predicates(dictionary, card_p);
+    predicates.push(dictionary.rdftype);
+    with_properties(
+        predicates,
+        dictionary
+            .ts
+            .pairs(on_property, 0)
+            .iter()
+            .map(|pair| pair[1]),
+    )
+}
+
+/// General method to apply a rule of the cardinality class
+///
+/// For each restriction of cardinality 1 accepted by `filter`,
+/// and each individual `u` of the restricted class,
+/// its first value which is not a literal is inferred to be `owl:sameAs` each of the others,
+/// as in the rules of the delta class.
+///
+/// If `delta` is given, only the individuals having a new type, value, or type of value are checked.
+fn apply_max_one_rule<F>(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    card_p: u32,
+    qualified: bool,
+    filter: F,
+) -> TripleStore
+where
+    F: Fn(&CardinalityRestriction) -> bool,
+{
+    let ts = &graph.dictionary.ts;
+    let rdftype = graph.dictionary.rdftype as u64;
+    let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+    let full = touches(delta, &restriction_predicates(&graph.dictionary, card_p));
+    let mut output = graph.new_output();
+    for restriction in cardinality_restrictions(graph, card_p, 1, qualified) {
+        if !filter(&restriction) {
+            continue;
+        }
+        let x = restriction.class;
+        let p_idx = NodeDictionary::prop_idx_to_idx(restriction.property);
+        let mut candidates: Vec<u64> = match delta {
+            Some(delta) if !full => {
+                let mut candidates: Vec<u64> = delta
+                    .pairs_with(type_idx, 1, x)
+                    .iter()
+                    .map(|pair| pair[1])
+                    .chain(delta.pairs(p_idx, 0).iter().map(|pair| pair[0]))
+                    .collect();
+                if let Some(c) = restriction.on_class {
+                    for type_pair in delta.pairs_with(type_idx, 1, c) {
+                        candidates.extend(
+                            ts.pairs_with(p_idx, 1, type_pair[1])
+                                .iter()
+                                .map(|pair| pair[1]),
+                        );
+                    }
+                }
+                candidates
+            }
+            _ => ts
+                .pairs_with(type_idx, 1, x)
+                .iter()
+                .map(|pair| pair[1])
+                .collect(),
+        };
+        candidates.sort_unstable();
+        candidates.dedup();
+        for u in candidates {
+            if !ts.contains_pair(type_idx, [u, x]) {
+                continue;
+            }
+            let mut common = restriction.triples.clone();
+            common.push([u, rdftype, x]);
+            link_same_as(graph, &common, restriction.values(graph, u), &mut output);
+        }
+    }
+    output
+}
+
+/// The CLS-MAXC2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:maxCardinality "1"^^xsd:nonNegativeInteger
+/// - x owl:onProperty p
+/// - u rdf:type x
+/// - u p y1
+/// - u p y2
+/// Head:
+/// - y1 owl:sameAs y2
+///
+/// As a constraint, it reports the literals y1 and y2 of distinct values.
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+/// use sophia::term::RcTerm;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix owl: <http://www.w3.org/2002/07/owl#> .
+///     @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+///
+///     :OneMother owl:maxCardinality "1"^^xsd:nonNegativeInteger .
+///     :OneMother owl:onProperty :mother .
+///     :Bart a :OneMother .
+///     :Bart :mother :Marge .
+///     :Bart :mother :MargeSimpson .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::OWL2RL);
+/// rules.fire_all(&mut graph);
+///
+/// let index = |iri| {
+///     let term = RcTerm::new_iri(iri).unwrap();
+///     graph.dictionary.get_index(&term).unwrap()
+/// };
+/// assert_eq!(
+///     index("http://example.org/Marge"),
+///     index("http://example.org/MargeSimpson")
+/// );
+/// ```
+pub struct CLS_MAXC2;
+
+impl Rule for CLS_MAXC2 {
+    fn name(&self) -> &str {
+        "CLS-MAXC2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(cardinality_inputs(dictionary, dictionary.maxCardinality))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlsameAs])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        apply_max_one_rule(graph, delta, graph.dictionary.maxCardinality, false, |_| {
+            true
+        })
+    }
+}
+
+impl Constraint for CLS_MAXC2 {
+    fn name(&self) -> &str {
+        "CLS-MAXC2"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let card_p = graph.dictionary.maxCardinality;
+        check_max_one_literals(self, graph, card_p, false, |_| true)
+    }
+}
+
+/// The CLS-MAXQC3 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:maxQualifiedCardinality "1"^^xsd:nonNegativeInteger
+/// - x owl:onProperty p
+/// - x owl:onClass c
+/// - u rdf:type x
+/// - u p y1
+/// - y1 rdf:type c
+/// - u p y2
+/// - y2 rdf:type c
+/// Head:
+/// - y1 owl:sameAs y2
+///
+/// As a constraint, it reports the literals y1 and y2 of distinct values.
+pub struct CLS_MAXQC3;
+
+impl Rule for CLS_MAXQC3 {
+    fn name(&self) -> &str {
+        "CLS-MAXQC3"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(cardinality_inputs(
+            dictionary,
+            dictionary.maxQualifiedCardinality,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlsameAs])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let card_p = graph.dictionary.maxQualifiedCardinality;
+        apply_max_one_rule(graph, delta, card_p, true, |restriction| {
+            restriction.checks_type(graph)
+        })
+    }
+}
+
+impl Constraint for CLS_MAXQC3 {
+    fn name(&self) -> &str {
+        "CLS-MAXQC3"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let card_p = graph.dictionary.maxQualifiedCardinality;
+        check_max_one_literals(self, graph, card_p, true, |restriction| {
+            restriction.checks_type(graph)
+        })
+    }
+}
+
+/// The CLS-MAXQC4 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:maxQualifiedCardinality "1"^^xsd:nonNegativeInteger
+/// - x owl:onProperty p
+/// - x owl:onClass owl:Thing
+/// - u rdf:type x
+/// - u p y1
+/// - u p y2
+/// Head:
+/// - y1 owl:sameAs y2
+///
+/// As a constraint, it reports the literals y1 and y2 of distinct values.
+pub struct CLS_MAXQC4;
+
+impl Rule for CLS_MAXQC4 {
+    fn name(&self) -> &str {
+        "CLS-MAXQC4"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(cardinality_inputs(
+            dictionary,
+            dictionary.maxQualifiedCardinality,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlsameAs])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let card_p = graph.dictionary.maxQualifiedCardinality;
+        apply_max_one_rule(graph, delta, card_p, true, |restriction| {
+            !restriction.checks_type(graph)
+        })
+    }
+}
+
+impl Constraint for CLS_MAXQC4 {
+    fn name(&self) -> &str {
+        "CLS-MAXQC4"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let card_p = graph.dictionary.maxQualifiedCardinality;
+        check_max_one_literals(self, graph, card_p, true, |restriction| {
+            !restriction.checks_type(graph)
+        })
+    }
+}
+
+/// Check that no individual has two literal values known to be distinct
+/// for the restrictions of cardinality 1 of property `card_p` accepted by `filter`
+fn check_max_one_literals<C, F>(
+    constraint: &C,
+    graph: &InfGraph,
+    card_p: u32,
+    qualified: bool,
+    filter: F,
+) -> Vec<Inconsistency>
+where
+    C: Constraint + ?Sized,
+    F: Fn(&CardinalityRestriction) -> bool,
+{
+    let rdftype = graph.dictionary.rdftype as u64;
+    let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+    let mut inconsistencies = Vec::new();
+    for restriction in cardinality_restrictions(graph, card_p, 1, qualified) {
+        if !filter(&restriction) {
+            continue;
+        }
+        let x = restriction.class;
+        for type_pair in graph.dictionary.ts.pairs_with(type_idx, 1, x) {
+            let u = type_pair[1];
+            let values = restriction.values(graph, u);
+            let mut literals = values
+                .iter()
+                .filter(|(y, _)| graph.dictionary.is_literal(*y));
+            if let Some((first, premises_1)) = literals.next() {
+                let distinct =
+                    |(y, _): &&(u64, Vec<[u64; 3]>)| graph.dictionary.distinct_literals(*first, *y);
+                for (_, premises_2) in literals.filter(distinct) {
+                    let mut triples = restriction.triples.clone();
+                    triples.push([u, rdftype, x]);
+                    triples.extend(premises_1);
+                    triples.extend(premises_2);
+                    inconsistencies.push(constraint.violation(triples));
+                }
+            }
+        }
+    }
+    inconsistencies
+}
+
+/// Check the restrictions of cardinality 0 of property `card_p` accepted by `filter`
+fn check_max_zero<C, F>(
+    constraint: &C,
+    graph: &InfGraph,
+    card_p: u32,
+    qualified: bool,
+    filter: F,
+) -> Vec<Inconsistency>
+where
+    C: Constraint + ?Sized,
+    F: Fn(&CardinalityRestriction) -> bool,
+{
+    let rdftype = graph.dictionary.rdftype as u64;
+    let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+    let mut inconsistencies = Vec::new();
+    for restriction in cardinality_restrictions(graph, card_p, 0, qualified) {
+        if !filter(&restriction) {
+            continue;
+        }
+        let x = restriction.class;
+        for type_pair in graph.dictionary.ts.pairs_with(type_idx, 1, x) {
+            let u = type_pair[1];
+            for (_, value_triples) in restriction.values(graph, u) {
+                let mut triples = restriction.triples.clone();
+                triples.push([u, rdftype, x]);
+                triples.extend(value_triples);
+                inconsistencies.push(constraint.violation(triples));
+            }
+        }
+    }
+    inconsistencies
+}
+
+/// The CLS-MAXC1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:maxCardinality "0"^^xsd:nonNegativeInteger
+/// - x owl:onProperty p
+/// - u rdf:type x
+/// - u p y
+/// Head:
+/// - false
+pub struct CLS_MAXC1;
+
+impl Constraint for CLS_MAXC1 {
+    fn name(&self) -> &str {
+        "CLS-MAXC1"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_max_zero(self, graph, graph.dictionary.maxCardinality, false, |_| {
+            true
+        })
+    }
+}
+
+/// The CLS-MAXQC1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:maxQualifiedCardinality "0"^^xsd:nonNegativeInteger
+/// - x owl:onProperty p
+/// - x owl:onClass c
+/// - u rdf:type x
+/// - u p y
+/// - y rdf:type c
+/// Head:
+/// - false
+pub struct CLS_MAXQC1;
+
+impl Constraint for CLS_MAXQC1 {
+    fn name(&self) -> &str {
+        "CLS-MAXQC1"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let card_p = graph.dictionary.maxQualifiedCardinality;
+        check_max_zero(self, graph, card_p, true, |restriction| {
+            restriction.checks_type(graph)
+        })
+    }
+}
+
+/// The CLS-MAXQC2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:maxQualifiedCardinality "0"^^xsd:nonNegativeInteger
+/// - x owl:onProperty p
+/// - x owl:onClass owl:Thing
+/// - u rdf:type x
+/// - u p y
+/// Head:
+/// - false
+pub struct CLS_MAXQC2;
+
+impl Constraint for CLS_MAXQC2 {
+    fn name(&self) -> &str {
+        "CLS-MAXQC2"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let card_p = graph.dictionary.maxQualifiedCardinality;
+        check_max_zero(self, graph, card_p, true, |restriction| {
+            !restriction.checks_type(graph)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::ConstraintSet;
+    use crate::test_utils::*;
+
+    #[test]
+    fn cls_maxc2() {
+        let graph = saturate(
+            r#"
+            :OneMother owl:maxCardinality "1"^^xsd:nonNegativeInteger .
+            :OneMother owl:onProperty :mother .
+            :Bart a :OneMother .
+            :Bart :mother :Marge, :MargeSimpson .
+            :Rod :mother :Maude, :MaudeFlanders .
+            "#,
+            vec![Box::new(CLS_MAXC2)],
+        );
+        assert_eq!(index(&graph, ":Marge"), index(&graph, ":MargeSimpson"));
+        assert_ne!(index(&graph, ":Maude"), index(&graph, ":MaudeFlanders"));
+    }
+
+    #[test]
+    fn cls_maxc2_other_cardinality() {
+        let graph = saturate(
+            r#"
+            :TwoParents owl:maxCardinality "2"^^xsd:nonNegativeInteger .
+            :TwoParents owl:onProperty :parent .
+            :Bart a :TwoParents .
+            :Bart :parent :Homer, :Marge .
+            "#,
+            vec![Box::new(CLS_MAXC2)],
+        );
+        assert_ne!(index(&graph, ":Homer"), index(&graph, ":Marge"));
+    }
+
+    #[test]
+    fn cls_maxc2_links() {
+        let graph = graph(
+            r#"
+            :OneMother owl:maxCardinality "1"^^xsd:nonNegativeInteger .
+            :OneMother owl:onProperty :mother .
+            :Bart a :OneMother .
+            :Bart :mother "Marge", :Marge, :MargeSimpson, :MrsSimpson .
+            "#,
+        );
+        // the first value which is not a literal is linked to each of the others
+        let mut output = fire(&graph, &mut CLS_MAXC2, None);
+        assert_eq!(output.size(), 2);
+    }
+
+    #[test]
+    fn cls_maxqc3_delta() {
+        let graph = graph(
+            r#"
+            :OneDaughter owl:maxQualifiedCardinality "1"^^xsd:nonNegativeInteger .
+            :OneDaughter owl:onProperty :child .
+            :OneDaughter owl:onClass :female .
+            :Homer a :OneDaughter .
+            :Homer :child :Lisa, :LisaSimpson .
+            :Lisa a :female . :LisaSimpson a :female .
+            :Homer :friend :Lenny .
+            "#,
+        );
+        // a new type of a value checks the individuals having this value
+        let delta = [[":LisaSimpson", "rdf:type", ":female"]];
+        let output = fire(&graph, &mut CLS_MAXQC3, Some(&delta));
+        assert!(
+            contains(&graph, &output, ":Lisa", "owl:sameAs", ":LisaSimpson")
+                || contains(&graph, &output, ":LisaSimpson", "owl:sameAs", ":Lisa")
+        );
+        let delta = [[":Homer", ":friend", ":Lenny"]];
+        assert!(fire(&graph, &mut CLS_MAXQC3, Some(&delta)).is_empty());
+    }
+
+    #[test]
+    fn cls_maxqc3_4() {
+        let rep = r#"
+            :OneDaughter owl:maxQualifiedCardinality "1"^^xsd:nonNegativeInteger .
+            :OneDaughter owl:onProperty :child .
+            :OneDaughter owl:onClass :female .
+            :OneChild owl:maxQualifiedCardinality "1"^^xsd:nonNegativeInteger .
+            :OneChild owl:onProperty :friend .
+            :OneChild owl:onClass owl:Thing .
+            :Homer a :OneDaughter, :OneChild .
+            :Homer :child :Lisa, :LisaSimpson, :Bart .
+            :Lisa a :female . :LisaSimpson a :female .
+            :Homer :friend :Lenny, :LennyLeonard .
+        "#;
+        let graph = saturate(rep, vec![Box::new(CLS_MAXQC3)]);
+        assert_eq!(index(&graph, ":Lisa"), index(&graph, ":LisaSimpson"));
+        assert_ne!(index(&graph, ":Lisa"), index(&graph, ":Bart"));
+        assert_ne!(index(&graph, ":Lenny"), index(&graph, ":LennyLeonard"));
+        let graph = saturate(rep, vec![Box::new(CLS_MAXQC4)]);
+        assert_eq!(index(&graph, ":Lenny"), index(&graph, ":LennyLeonard"));
+        assert_ne!(index(&graph, ":Lisa"), index(&graph, ":LisaSimpson"));
+    }
+
+    #[test]
+    fn cls_maxc2_literals() {
+        let graph = saturate(
+            r#"
+            :OneName owl:maxCardinality "1"^^xsd:nonNegativeInteger .
+            :OneName owl:onProperty :name .
+            :Bart a :OneName .
+            :Bart :name "Bart", "Bartholomew" .
+            "#,
+            vec![Box::new(CLS_MAXC2)],
+        );
+        let same_as = NodeDictionary::prop_idx_to_idx(graph.dictionary.owlsameAs as u64);
+        assert!(graph.dictionary.ts.pairs(same_as, 0).is_empty());
+        let constraints: Vec<Box<dyn Constraint>> = vec![Box::new(CLS_MAXC2)];
+        let inconsistencies = constraints.check_all(&graph);
+        assert_eq!(inconsistencies.len(), 1);
+        assert_eq!(inconsistencies[0].rule, "CLS-MAXC2");
+        assert_eq!(inconsistencies[0].triples.len(), 5);
+    }
+
+    #[test]
+    fn cls_maxqc4_equal_literals() {
+        let graph = graph(
+            r#"
+            :OneAge owl:maxQualifiedCardinality "1"^^xsd:nonNegativeInteger .
+            :OneAge owl:onProperty :age .
+            :OneAge owl:onClass owl:Thing .
+            :Bart a :OneAge .
+            :Bart :age "10"^^xsd:integer .
+            :Bart :age "+10"^^xsd:int .
+            "#,
+        );
+        assert!(Constraint::check(&CLS_MAXQC4, &graph).is_empty());
+    }
+
+    #[test]
+    fn cls_maxc1() {
+        let graph = graph(
+            r#"
+            :NoPet owl:maxCardinality "0"^^xsd:nonNegativeInteger .
+            :NoPet owl:onProperty :hasPet .
+            :Bart a :NoPet . :Bart :hasPet :SantasLittleHelper .
+            :Lisa a :NoPet .
+            "#,
+        );
+        let constraints: Vec<Box<dyn Constraint>> = vec![Box::new(CLS_MAXC1)];
+        let inconsistencies = constraints.check_all(&graph);
+        assert_eq!(inconsistencies.len(), 1);
+        assert_eq!(inconsistencies[0].triples.len(), 4);
+    }
+}
@@ -0,0 +1,305 @@
+//! The chain class groups the following rule :
+//! <ul>
+//! <li>PRP-SPO2</li>
+//! </ul>
+//!
+//! It uses property chains `p owl:propertyChainAxiom l`,
+//! where `l` is the head of a RDF list of properties `p1 ... pn`.
+//! The chain is computed by joining the object-subject vector of the pairs
+//! composed so far with the subject-object vector of the next property.
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::Rule;
+use crate::rules::{touches, with_properties};
+
+/// A property chain `property owl:propertyChainAxiom head`, where `head` is the head of the list `members`
+struct PropertyChain {
+    property: u64,
+    head: u64,
+    members: Vec<u64>,
+}
+
+/// Return the property chains found in the store
+///
+/// Chains which are empty, malformed, or use a term which is not a property, are ignored.
+fn property_chains(graph: &InfGraph) -> Vec<PropertyChain> {
+    let chain_p = graph.dictionary.propertyChainAxiom as u64;
+    graph
+        .dictionary
+        .ts
+        .pairs(NodeDictionary::prop_idx_to_idx(chain_p), 0)
+        .iter()
+        .filter(|pair| NodeDictionary::is_property(pair[0]))
+        .filter_map(|pair| {
+            let members = graph.dictionary.list(pair[1])?;
+            if members.is_empty() || !members.iter().all(|&p| NodeDictionary::is_property(p)) {
+                return None;
+            }
+            Some(PropertyChain {
+                property: pair[0],
+                head: pair[1],
+                members,
+            })
+        })
+        .collect()
+}
+
+/// Compose the pairs of the properties of `chain`,
+/// taking the pairs of the `k`-th property from `sources(k)`
+///
+/// Return the pairs `[u1, un+1]` linked by the chain,
+/// with the nodes `u1 ... un+1` of one of their paths if `with_paths`
+/// (otherwise the paths are empty).
+fn compose<'a, F>(chain: &[u64], sources: F, with_paths: bool) -> Vec<([u64; 2], Vec<u64>)>
+where
+    F: Fn(usize) -> &'a TripleStore,
+{
+    // pairs `[last, first]`, sorted
+    let mut current: Vec<([u64; 2], Vec<u64>)> = sources(0)
+        .pairs(NodeDictionary::prop_idx_to_idx(chain[0]), 1)
+        .iter()
+        .map(|pair| {
+            let path = if with_paths {
+                vec![pair[1], pair[0]]
+            } else {
+                vec![]
+            };
+            (*pair, path)
+        })
+        .collect();
+    for (k, &p) in chain.iter().enumerate().skip(1) {
+        let right = sources(k).pairs(NodeDictionary::prop_idx_to_idx(p), 0);
+        let mut next = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < current.len() && j < right.len() {
+            let b = current[i].0[0];
+            if b < right[j][0] {
+                i += 1;
+            } else if right[j][0] < b {
+                j += 1;
+            } else {
+                let j_end = j + right[j..].partition_point(|pair| pair[0] == b);
+                while i < current.len() && current[i].0[0] == b {
+                    let (pair, path) = &current[i];
+                    for right_pair in &right[j..j_end] {
+                        let mut path = path.clone();
+                        if with_paths {
+                            path.push(right_pair[1]);
+                        }
+                        next.push(([right_pair[1], pair[1]], path));
+                    }
+                    i += 1;
+                }
+                j = j_end;
+            }
+        }
+        next.sort_by_key(|pair| pair.0);
+        next.dedup_by(|a, b| a.0 == b.0);
+        current = next;
+        if current.is_empty() {
+            break;
+        }
+    }
+    current
+        .into_iter()
+        .map(|(pair, path)| ([pair[1], pair[0]], path))
+        .collect()
+}
+
+/// The PRP-SPO2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - p owl:propertyChainAxiom (p1 ... pn)
+/// - u1 p1 u2
+/// - ...
+/// - un pn un+1
+/// Head:
+/// - u1 p un+1
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+/// use sophia::term::RcTerm;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix owl: <http://www.w3.org/2002/07/owl#> .
+///
+///     :hasUncle owl:propertyChainAxiom ( :hasParent :hasBrother ) .
+///     :Bart :hasParent :Homer .
+///     :Homer :hasBrother :Herb .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::OWL2RL);
+/// rules.fire_all(&mut graph);
+///
+/// let index = |iri| {
+///     let term = RcTerm::new_iri(iri).unwrap();
+///     graph.dictionary.get_index(&term).unwrap()
+/// };
+/// let has_uncle = NodeDictionary::prop_idx_to_idx(index("http://example.org/hasUncle"));
+/// assert!(graph.dictionary.ts.contains_pair(
+///     has_uncle,
+///     [index("http://example.org/Bart"), index("http://example.org/Herb")]
+/// ));
+/// ```
+pub struct PRP_SPO2;
+
+impl Rule for PRP_SPO2 {
+    fn name(&self) -> &str {
+        "PRP-SPO2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let chain_p = NodeDictionary::prop_idx_to_idx(dictionary.propertyChainAxiom as u64);
+        let members = dictionary
+            .ts
+            .pairs(chain_p, 0)
+            .iter()
+            .filter_map(|pair| dictionary.list(pair[1]))
+            .flatten();
+        Some(with_properties(
+            vec![
+                dictionary.propertyChainAxiom,
+                dictionary.rdffirst,
+                dictionary.rdfrest,
+            ],
+            members,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let chain_p = NodeDictionary::prop_idx_to_idx(dictionary.propertyChainAxiom as u64);
+        Some(with_properties(
+            vec![],
+            dictionary.ts.pairs(chain_p, 0).iter().map(|pair| pair[0]),
+        ))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let ts = &graph.dictionary.ts;
+        let chain_p = graph.dictionary.propertyChainAxiom as u64;
+        let dictionary = &graph.dictionary;
+        let full = touches(
+            delta,
+            &[
+                dictionary.propertyChainAxiom,
+                dictionary.rdffirst,
+                dictionary.rdfrest,
+            ],
+        );
+        let mut output = graph.new_output();
+        let with_paths = output.records_premises();
+        for chain in property_chains(graph) {
+            let n = chain.members.len();
+            let mut pairs = Vec::new();
+            match delta {
+                // the triples of `delta` are used by the `k`-th property of the chain
+                Some(delta) if !full => {
+                    for k in 0..n {
+                        let sources = |i| if i == k { delta } else { ts };
+                        pairs.extend(compose(&chain.members, sources, with_paths));
+                    }
+                }
+                _ => pairs = compose(&chain.members, |_| ts, with_paths),
+            }
+            for ([u1, un], path) in pairs {
+                let mut premises = vec![[chain.property, chain_p, chain.head]];
+                premises.extend(
+                    path.windows(2)
+                        .zip(&chain.members)
+                        .map(|(nodes, &p)| [nodes[0], p, nodes[1]]),
+                );
+                output.add_inferred_triple([u1, chain.property, un], &premises);
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn prp_spo2() {
+        let graph = saturate(
+            r#"
+            :hasUncle owl:propertyChainAxiom ( :hasParent :hasBrother ) .
+            :Bart :hasParent :Homer . :Homer :hasBrother :Herb .
+            :Lisa :hasParent :Marge . :Marge :hasSister :Patty .
+            "#,
+            vec![Box::new(PRP_SPO2)],
+        );
+        assert!(has(&graph, ":Bart", ":hasUncle", ":Herb"));
+        assert!(!has(&graph, ":Lisa", ":hasUncle", ":Patty"));
+    }
+
+    #[test]
+    fn prp_spo2_long_chain() {
+        let graph = saturate(
+            r#"
+            :suppliedBy owl:propertyChainAxiom ( :partOf :madeBy :locatedIn ) .
+            :wheel :partOf :car . :car :madeBy :acme . :acme :locatedIn :detroit .
+            :bolt :partOf :car .
+            :door :partOf :truck .
+            "#,
+            vec![Box::new(PRP_SPO2)],
+        );
+        assert!(has(&graph, ":wheel", ":suppliedBy", ":detroit"));
+        assert!(has(&graph, ":bolt", ":suppliedBy", ":detroit"));
+        assert!(!has(&graph, ":door", ":suppliedBy", ":detroit"));
+    }
+
+    #[test]
+    fn prp_spo2_provenance() {
+        let graph = saturate_with_provenance(
+            r#"
+            :suppliedBy owl:propertyChainAxiom ( :partOf :madeBy :locatedIn ) .
+            :wheel :partOf :car . :car :madeBy :acme . :acme :locatedIn :detroit .
+            "#,
+            vec![Box::new(PRP_SPO2)],
+        );
+        // the axiom, and one triple per property of the chain
+        let derivation = graph
+            .explain(&iri(":wheel"), &iri(":suppliedBy"), &iri(":detroit"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("PRP-SPO2"));
+        let objects: Vec<_> = derivation.premises[1..]
+            .iter()
+            .map(|premise| premise.triple[2].value())
+            .collect();
+        let expected: Vec<_> = [":car", ":acme", ":detroit"]
+            .iter()
+            .map(|name| iri(name).value())
+            .collect();
+        assert_eq!(objects, expected);
+    }
+
+    #[test]
+    fn prp_spo2_delta() {
+        let graph = graph(
+            r#"
+            :hasUncle owl:propertyChainAxiom ( :hasParent :hasBrother ) .
+            :Bart :hasParent :Homer . :Lisa :hasParent :Homer .
+            :Homer :hasBrother :Herb .
+            "#,
+        );
+        // a new triple of any property of the chain is joined with the store
+        let delta = [[":Lisa", ":hasParent", ":Homer"]];
+        let output = fire(&graph, &mut PRP_SPO2, Some(&delta));
+        assert!(contains(&graph, &output, ":Lisa", ":hasUncle", ":Herb"));
+        assert!(!contains(&graph, &output, ":Bart", ":hasUncle", ":Herb"));
+        let delta = [[":Homer", ":hasBrother", ":Herb"]];
+        let output = fire(&graph, &mut PRP_SPO2, Some(&delta));
+        assert!(contains(&graph, &output, ":Bart", ":hasUncle", ":Herb"));
+        assert!(contains(&graph, &output, ":Lisa", ":hasUncle", ":Herb"));
+    }
+}
@@ -0,0 +1,782 @@
+//! The constraints of the reasoner, i.e. the OWL 2 RL rules whose head is `false`,
+//! which detect the inconsistencies of a graph
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::rules::instances_of;
+use crate::rules::MaybeSend;
+use crate::rules::Profile;
+
+use sophia::term::ArcTerm;
+
+/// An inconsistency of a graph, found by a [`Constraint`](trait.Constraint.html)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inconsistency {
+    /// The name of the violated constraint, e.g. `CAX-DW`
+    pub rule: String,
+    /// The offending triples
+    pub triples: Vec<[u64; 3]>,
+}
+
+impl Inconsistency {
+    /// Return the offending triples, as terms of `graph`
+    pub fn terms<'a>(&self, graph: &'a InfGraph) -> Vec<[&'a ArcTerm; 3]> {
+        let get_term = |index| graph.dictionary.get_term(index);
+        self.triples
+            .iter()
+            .map(|triple| {
+                [
+                    get_term(triple[0]),
+                    get_term(triple[1]),
+                    get_term(triple[2]),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// A constraint of the reasoner
+///
+/// Constraints are checked against a graph which has already been saturated by a
+/// [`RuleSet`](trait.RuleSet.html), using the canonical index of each `owl:sameAs` clique.
+pub trait Constraint: MaybeSend {
+    /// The name of this constraint, e.g. `CAX-DW`
+    fn name(&self) -> &str;
+
+    /// Check this constraint against `graph`, and return the inconsistencies found
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency>;
+
+    /// Return an inconsistency of this constraint, caused by `triples`
+    fn violation(&self, triples: Vec<[u64; 3]>) -> Inconsistency {
+        Inconsistency {
+            rule: self.name().to_string(),
+            triples,
+        }
+    }
+}
+
+/// A set of Constraint, which can be checked against a InfGraph
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix owl: <http://www.w3.org/2002/07/owl#> .
+///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+///
+///     :Bart a :human .
+///     :Bart a :robot .
+///     :human rdfs:subClassOf :animal .
+///     :animal owl:disjointWith :robot .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::OWL2RL);
+/// rules.fire_all(&mut graph);
+///
+/// let constraints = <Vec<Box<dyn Constraint>> as ConstraintSet>::new();
+/// let inconsistencies = constraints.check_all(&graph);
+/// assert_eq!(inconsistencies.len(), 1);
+/// assert_eq!(inconsistencies[0].rule, "CAX-DW");
+/// assert_eq!(inconsistencies[0].triples.len(), 3);
+/// ```
+pub trait ConstraintSet {
+    /// Return the constraints of the OWL 2 RL profile
+    fn new() -> Self;
+    /// Return the constraints of the given profile
+    fn with_profile(profile: Profile) -> Self;
+    /// Check every constraint against `graph`, and return all the inconsistencies found
+    fn check_all(&self, graph: &InfGraph) -> Vec<Inconsistency>;
+}
+
+impl ConstraintSet for Vec<Box<dyn Constraint>> {
+    fn new() -> Self {
+        Self::with_profile(Profile::OWL2RL)
+    }
+    fn with_profile(profile: Profile) -> Self {
+        profile.constraints()
+    }
+    fn check_all(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        self.iter()
+            .flat_map(|constraint| constraint.check(graph))
+            .collect()
+    }
+}
+
+/// Return the elements found as second element of a pair in both sorted slices
+///
+/// The pairs of each slice must share their first element.
+fn common_seconds(pairs_1: &[[u64; 2]], pairs_2: &[[u64; 2]]) -> Vec<u64> {
+    let mut common = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < pairs_1.len() && j < pairs_2.len() {
+        if pairs_1[i][1] < pairs_2[j][1] {
+            i += 1;
+        } else if pairs_2[j][1] < pairs_1[i][1] {
+            j += 1;
+        } else {
+            common.push(pairs_1[i][1]);
+            i += 1;
+            j += 1;
+        }
+    }
+    common
+}
+
+/// Return the pairs found in both sorted slices
+fn common_pairs(pairs_1: &[[u64; 2]], pairs_2: &[[u64; 2]]) -> Vec<[u64; 2]> {
+    let mut common = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < pairs_1.len() && j < pairs_2.len() {
+        if pairs_1[i] < pairs_2[j] {
+            i += 1;
+        } else if pairs_2[j] < pairs_1[i] {
+            j += 1;
+        } else {
+            common.push(pairs_1[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+    common
+}
+
+/// A resource `x` of type `class`, whose members are given by the list `x list_p (z1 ... zn)`
+struct MemberList {
+    /// The triples defining the list
+    triples: Vec<[u64; 3]>,
+    members: Vec<u64>,
+}
+
+/// Return the well-formed member lists of property `list_p` of the instances of `class`
+fn member_lists(graph: &InfGraph, class: u64, list_p: u64) -> Vec<MemberList> {
+    let ts = &graph.dictionary.ts;
+    let rdftype = graph.dictionary.rdftype as u64;
+    let mut lists = Vec::new();
+    for type_pair in ts.pairs_with(NodeDictionary::prop_idx_to_idx(rdftype), 1, class) {
+        let x = type_pair[1];
+        for pair in ts.pairs_with(NodeDictionary::prop_idx_to_idx(list_p), 0, x) {
+            if let Some(members) = graph.dictionary.list(pair[1]) {
+                lists.push(MemberList {
+                    triples: vec![[x, rdftype, class], [x, list_p, pair[1]]],
+                    members,
+                });
+            }
+        }
+    }
+    lists
+}
+
+/// Check that no individual belongs to two classes linked by `rule_p`
+fn check_disjoint_classes<C>(constraint: &C, graph: &InfGraph, rule_p: u64) -> Vec<Inconsistency>
+where
+    C: Constraint + ?Sized,
+{
+    let ts = &graph.dictionary.ts;
+    let rdftype = graph.dictionary.rdftype as u64;
+    let id = NodeDictionary::prop_idx_to_idx(rdftype);
+    let mut inconsistencies = Vec::new();
+    for pair in ts.pairs(NodeDictionary::prop_idx_to_idx(rule_p), 0) {
+        let [c1, c2] = *pair;
+        for x in common_seconds(ts.pairs_with(id, 1, c1), ts.pairs_with(id, 1, c2)) {
+            inconsistencies.push(constraint.violation(vec![
+                [c1, rule_p, c2],
+                [x, rdftype, c1],
+                [x, rdftype, c2],
+            ]));
+        }
+    }
+    inconsistencies
+}
+
+/// The CAX-DW rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:disjointWith c2
+/// - x rdf:type c1
+/// - x rdf:type c2
+/// Head:
+/// - false
+pub struct CAX_DW;
+
+impl Constraint for CAX_DW {
+    fn name(&self) -> &str {
+        "CAX-DW"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_disjoint_classes(self, graph, graph.dictionary.owldisjoinWith as u64)
+    }
+}
+
+/// The CLS-COM rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:complementOf c2
+/// - x rdf:type c1
+/// - x rdf:type c2
+/// Head:
+/// - false
+pub struct CLS_COM;
+
+impl Constraint for CLS_COM {
+    fn name(&self) -> &str {
+        "CLS-COM"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_disjoint_classes(self, graph, graph.dictionary.owlcomplementOf as u64)
+    }
+}
+
+/// The CAX-ADC rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x rdf:type owl:AllDisjointClasses
+/// - x owl:members (c1 ... cn)
+/// - z rdf:type ci
+/// - z rdf:type cj, with i != j
+/// Head:
+/// - false
+pub struct CAX_ADC;
+
+impl Constraint for CAX_ADC {
+    fn name(&self) -> &str {
+        "CAX-ADC"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let ts = &graph.dictionary.ts;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+        let all_disjoint = graph.dictionary.owlallDisjointClasses as u64;
+        let members = graph.dictionary.members as u64;
+        let mut inconsistencies = Vec::new();
+        for list in member_lists(graph, all_disjoint, members) {
+            for (i, &ci) in list.members.iter().enumerate() {
+                for &cj in &list.members[i + 1..] {
+                    let instances_i = ts.pairs_with(type_idx, 1, ci);
+                    let instances_j = ts.pairs_with(type_idx, 1, cj);
+                    for z in common_seconds(instances_i, instances_j) {
+                        let mut triples = list.triples.clone();
+                        triples.push([z, rdftype, ci]);
+                        triples.push([z, rdftype, cj]);
+                        inconsistencies.push(self.violation(triples));
+                    }
+                }
+            }
+        }
+        inconsistencies
+    }
+}
+
+/// The CLS-NOTHING2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x rdf:type owl:Nothing
+/// Head:
+/// - false
+pub struct CLS_NOTHING2;
+
+impl Constraint for CLS_NOTHING2 {
+    fn name(&self) -> &str {
+        "CLS-NOTHING2"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let rdftype = graph.dictionary.rdftype as u64;
+        let nothing = graph.dictionary.nothing as u64;
+        graph
+            .dictionary
+            .ts
+            .pairs_with(NodeDictionary::prop_idx_to_idx(rdftype), 1, nothing)
+            .iter()
+            .map(|pair| self.violation(vec![[pair[1], rdftype, nothing]]))
+            .collect()
+    }
+}
+
+/// The PRP-IRP rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - p rdf:type owl:IrreflexiveProperty
+/// - x p x
+/// Head:
+/// - false
+pub struct PRP_IRP;
+
+impl Constraint for PRP_IRP {
+    fn name(&self) -> &str {
+        "PRP-IRP"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let rdftype = graph.dictionary.rdftype as u64;
+        let irreflexive = graph.dictionary.irreflexiveProperty as u64;
+        let mut inconsistencies = Vec::new();
+        for p in instances_of(&graph.dictionary, irreflexive) {
+            if !NodeDictionary::is_property(p) {
+                continue;
+            }
+            let pairs = graph
+                .dictionary
+                .ts
+                .pairs(NodeDictionary::prop_idx_to_idx(p), 0);
+            for pair in pairs.iter().filter(|pair| pair[0] == pair[1]) {
+                inconsistencies
+                    .push(self.violation(vec![[p, rdftype, irreflexive], [pair[0], p, pair[1]]]));
+            }
+        }
+        inconsistencies
+    }
+}
+
+/// The PRP-ASYP rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - p rdf:type owl:AsymmetricProperty
+/// - x p y
+/// - y p x
+/// Head:
+/// - false
+pub struct PRP_ASYP;
+
+impl Constraint for PRP_ASYP {
+    fn name(&self) -> &str {
+        "PRP-ASYP"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let rdftype = graph.dictionary.rdftype as u64;
+        let asymmetric = graph.dictionary.asymmetricProperty as u64;
+        let mut inconsistencies = Vec::new();
+        for p in instances_of(&graph.dictionary, asymmetric) {
+            if !NodeDictionary::is_property(p) {
+                continue;
+            }
+            let idx = NodeDictionary::prop_idx_to_idx(p);
+            let ts = &graph.dictionary.ts;
+            // each offending pair of triples is found twice, and only reported from x <= y
+            for &[x, y] in ts.pairs(idx, 0) {
+                if x <= y && ts.contains_pair(idx, [y, x]) {
+                    inconsistencies.push(self.violation(vec![
+                        [p, rdftype, asymmetric],
+                        [x, p, y],
+                        [y, p, x],
+                    ]));
+                }
+            }
+        }
+        inconsistencies
+    }
+}
+
+/// The PRP-PDW rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - p1 owl:propertyDisjointWith p2
+/// - x p1 y
+/// - x p2 y
+/// Head:
+/// - false
+pub struct PRP_PDW;
+
+impl Constraint for PRP_PDW {
+    fn name(&self) -> &str {
+        "PRP-PDW"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let ts = &graph.dictionary.ts;
+        let disjoint = graph.dictionary.owlpropertyDisjointWith as u64;
+        let mut inconsistencies = Vec::new();
+        for pair in ts.pairs(NodeDictionary::prop_idx_to_idx(disjoint), 0) {
+            let [p1, p2] = *pair;
+            if !NodeDictionary::is_property(p1) || !NodeDictionary::is_property(p2) {
+                continue;
+            }
+            let pairs_1 = ts.pairs(NodeDictionary::prop_idx_to_idx(p1), 0);
+            let pairs_2 = ts.pairs(NodeDictionary::prop_idx_to_idx(p2), 0);
+            for [x, y] in common_pairs(pairs_1, pairs_2) {
+                inconsistencies.push(self.violation(vec![
+                    [p1, disjoint, p2],
+                    [x, p1, y],
+                    [x, p2, y],
+                ]));
+            }
+        }
+        inconsistencies
+    }
+}
+
+/// The PRP-ADP rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x rdf:type owl:AllDisjointProperties
+/// - x owl:members (p1 ... pn)
+/// - u pi y
+/// - u pj y, with i != j
+/// Head:
+/// - false
+pub struct PRP_ADP;
+
+impl Constraint for PRP_ADP {
+    fn name(&self) -> &str {
+        "PRP-ADP"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let ts = &graph.dictionary.ts;
+        let all_disjoint = graph.dictionary.owlallDisjointProperties as u64;
+        let members = graph.dictionary.members as u64;
+        let mut inconsistencies = Vec::new();
+        for list in member_lists(graph, all_disjoint, members) {
+            for (i, &pi) in list.members.iter().enumerate() {
+                for &pj in &list.members[i + 1..] {
+                    if !NodeDictionary::is_property(pi) || !NodeDictionary::is_property(pj) {
+                        continue;
+                    }
+                    let pairs_i = ts.pairs(NodeDictionary::prop_idx_to_idx(pi), 0);
+                    let pairs_j = ts.pairs(NodeDictionary::prop_idx_to_idx(pj), 0);
+                    for [u, y] in common_pairs(pairs_i, pairs_j) {
+                        let mut triples = list.triples.clone();
+                        triples.push([u, pi, y]);
+                        triples.push([u, pj, y]);
+                        inconsistencies.push(self.violation(triples));
+                    }
+                }
+            }
+        }
+        inconsistencies
+    }
+}
+
+/// The EQ-DIFF1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:sameAs y
+/// - x owl:differentFrom y
+/// Head:
+/// - false
+///
+/// As `owl:sameAs` cliques are merged in the store, the offending triple is
+/// `x owl:differentFrom x`, where `x` is the canonical term of the clique
+/// (see [`InfGraph::equivalents`](../inferray/struct.InfGraph.html#method.equivalents)).
+pub struct EQ_DIFF1;
+
+impl Constraint for EQ_DIFF1 {
+    fn name(&self) -> &str {
+        "EQ-DIFF1"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let different = graph.dictionary.owldifferentFrom as u64;
+        graph
+            .dictionary
+            .ts
+            .pairs(NodeDictionary::prop_idx_to_idx(different), 0)
+            .iter()
+            .filter(|pair| pair[0] == pair[1])
+            .map(|pair| self.violation(vec![[pair[0], different, pair[1]]]))
+            .collect()
+    }
+}
+
+/// Check that no two members of the lists of property `list_p` of the instances of
+/// `owl:AllDifferent` are `owl:sameAs` each other
+///
+/// As `owl:sameAs` cliques are merged in the store, such members share their canonical term,
+/// which is given as both ends of the offending `owl:sameAs` triple.
+fn check_all_different<C>(constraint: &C, graph: &InfGraph, list_p: u64) -> Vec<Inconsistency>
+where
+    C: Constraint + ?Sized,
+{
+    let all_different = graph.dictionary.owlallDifferent as u64;
+    let same_as = graph.dictionary.owlsameAs as u64;
+    let mut inconsistencies = Vec::new();
+    for list in member_lists(graph, all_different, list_p) {
+        for (i, &zi) in list.members.iter().enumerate() {
+            for &zj in &list.members[i + 1..] {
+                if zi == zj {
+                    let mut triples = list.triples.clone();
+                    triples.push([zi, same_as, zj]);
+                    inconsistencies.push(constraint.violation(triples));
+                }
+            }
+        }
+    }
+    inconsistencies
+}
+
+/// The EQ-DIFF2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x rdf:type owl:AllDifferent
+/// - x owl:members (z1 ... zn)
+/// - zi owl:sameAs zj, with i != j
+/// Head:
+/// - false
+pub struct EQ_DIFF2;
+
+impl Constraint for EQ_DIFF2 {
+    fn name(&self) -> &str {
+        "EQ-DIFF2"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_all_different(self, graph, graph.dictionary.members as u64)
+    }
+}
+
+/// The EQ-DIFF3 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x rdf:type owl:AllDifferent
+/// - x owl:distinctMembers (z1 ... zn)
+/// - zi owl:sameAs zj, with i != j
+/// Head:
+/// - false
+pub struct EQ_DIFF3;
+
+impl Constraint for EQ_DIFF3 {
+    fn name(&self) -> &str {
+        "EQ-DIFF3"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_all_different(self, graph, graph.dictionary.owldistinctmembers as u64)
+    }
+}
+
+/// Check the negative property assertions whose target is given by `target_p`
+fn check_negative_assertions<C>(
+    constraint: &C,
+    graph: &InfGraph,
+    target_p: u64,
+) -> Vec<Inconsistency>
+where
+    C: Constraint + ?Sized,
+{
+    let ts = &graph.dictionary.ts;
+    let source_p = graph.dictionary.sourceIndividual as u64;
+    let assertion_p = graph.dictionary.owlassertionProperty as u64;
+    let mut inconsistencies = Vec::new();
+    for pair in ts.pairs(NodeDictionary::prop_idx_to_idx(source_p), 0) {
+        let [x, i1] = *pair;
+        for assertion_pair in ts.pairs_with(NodeDictionary::prop_idx_to_idx(assertion_p), 0, x) {
+            let p = assertion_pair[1];
+            if !NodeDictionary::is_property(p) {
+                continue;
+            }
+            for target_pair in ts.pairs_with(NodeDictionary::prop_idx_to_idx(target_p), 0, x) {
+                let i2 = target_pair[1];
+                if ts.contains_pair(NodeDictionary::prop_idx_to_idx(p), [i1, i2]) {
+                    inconsistencies.push(constraint.violation(vec![
+                        [x, source_p, i1],
+                        [x, assertion_p, p],
+                        [x, target_p, i2],
+                        [i1, p, i2],
+                    ]));
+                }
+            }
+        }
+    }
+    inconsistencies
+}
+
+/// The PRP-NPA1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:sourceIndividual i1
+/// - x owl:assertionProperty p
+/// - x owl:targetIndividual i2
+/// - i1 p i2
+/// Head:
+/// - false
+pub struct PRP_NPA1;
+
+impl Constraint for PRP_NPA1 {
+    fn name(&self) -> &str {
+        "PRP-NPA1"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_negative_assertions(self, graph, graph.dictionary.owltargetIndividual as u64)
+    }
+}
+
+/// The PRP-NPA2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:sourceIndividual i
+/// - x owl:assertionProperty p
+/// - x owl:targetValue lt
+/// - i p lt
+/// Head:
+/// - false
+pub struct PRP_NPA2;
+
+impl Constraint for PRP_NPA2 {
+    fn name(&self) -> &str {
+        "PRP-NPA2"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        check_negative_assertions(self, graph, graph.dictionary.targetValue as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Check `constraint` against `rep`, and return the number of triples of each inconsistency
+    fn check(constraint: Box<dyn Constraint>, rep: &str) -> Vec<usize> {
+        let graph = graph(rep);
+        vec![constraint]
+            .check_all(&graph)
+            .iter()
+            .map(|inconsistency| inconsistency.triples.len())
+            .collect()
+    }
+
+    #[test]
+    fn cax_dw_cls_com() {
+        let rep = ":Bart a :human, :robot . :Lisa a :human . :human owl:disjointWith :robot .";
+        assert_eq!(check(Box::new(CAX_DW), rep), vec![3]);
+        assert!(check(Box::new(CLS_COM), rep).is_empty());
+        let rep = ":Bart a :human, :robot . :Lisa a :human . :human owl:complementOf :robot .";
+        assert_eq!(check(Box::new(CLS_COM), rep), vec![3]);
+        assert!(check(Box::new(CAX_DW), rep).is_empty());
+    }
+
+    #[test]
+    fn cls_nothing2() {
+        assert_eq!(
+            check(Box::new(CLS_NOTHING2), ":Bart a owl:Nothing ."),
+            vec![1]
+        );
+        assert!(check(Box::new(CLS_NOTHING2), ":Bart a owl:Thing .").is_empty());
+    }
+
+    #[test]
+    fn cax_dw_inferred_type() {
+        let graph = saturate_profile(
+            r#"
+            :Bart a :boy, :robot .
+            :boy rdfs:subClassOf :human .
+            :human owl:disjointWith :robot .
+            "#,
+            Profile::RDFSPlus,
+        );
+        // the offending type of Bart is inferred
+        let inconsistencies = CAX_DW.check(&graph);
+        assert_eq!(inconsistencies.len(), 1);
+        assert_eq!(inconsistencies[0].rule, "CAX-DW");
+        let terms = inconsistencies[0].terms(&graph);
+        let bart = iri(":Bart").value();
+        assert!(terms
+            .iter()
+            .any(|t| t[0].value() == bart && t[2].value() == iri(":human").value()));
+    }
+
+    #[test]
+    fn common_seconds_of_groups() {
+        let pairs_1 = [[1, 2], [1, 4], [1, 5]];
+        let pairs_2 = [[7, 4], [7, 5], [7, 6]];
+        assert_eq!(common_seconds(&pairs_1, &pairs_2), vec![4, 5]);
+        assert!(common_seconds(&pairs_1, &[]).is_empty());
+    }
+
+    #[test]
+    fn prp_irp() {
+        let rep = ":parent a owl:IrreflexiveProperty . :Bart :parent :Bart . :Bart :parent :Homer . :Lisa :knows :Lisa .";
+        assert_eq!(check(Box::new(PRP_IRP), rep), vec![2]);
+    }
+
+    #[test]
+    fn prp_pdw() {
+        let rep = r#"
+            :parent owl:propertyDisjointWith :child .
+            :Bart :parent :Homer . :Bart :child :Homer .
+            :Lisa :parent :Marge . :Marge :child :Lisa .
+        "#;
+        assert_eq!(check(Box::new(PRP_PDW), rep), vec![3]);
+    }
+
+    #[test]
+    fn eq_diff1() {
+        let graph = saturate_profile(
+            r#"
+            :Bart owl:sameAs :ElBarto . :Bart owl:differentFrom :ElBarto .
+            :Lisa owl:differentFrom :Maggie .
+            "#,
+            Profile::OWL2RL,
+        );
+        let inconsistencies = vec![Box::new(EQ_DIFF1) as Box<dyn Constraint>].check_all(&graph);
+        assert_eq!(inconsistencies.len(), 1);
+    }
+
+    #[test]
+    fn prp_npa() {
+        let rep = r#"
+            :x owl:sourceIndividual :Bart . :x owl:assertionProperty :parent . :x owl:targetIndividual :Ned .
+            :y owl:sourceIndividual :Bart . :y owl:assertionProperty :age . :y owl:targetValue "10" .
+            :Bart :parent :Ned . :Bart :age "11" .
+        "#;
+        assert_eq!(check(Box::new(PRP_NPA1), rep), vec![4]);
+        assert!(check(Box::new(PRP_NPA2), rep).is_empty());
+    }
+
+    #[test]
+    fn eq_diff2_3() {
+        let graph = saturate_profile(
+            r#"
+            :Bart owl:sameAs :ElBarto .
+            :x a owl:AllDifferent . :x owl:members (:Bart :Lisa :ElBarto) .
+            :y a owl:AllDifferent . :y owl:distinctMembers (:Lisa :Maggie) .
+            "#,
+            Profile::OWL2RL,
+        );
+        let inconsistencies = vec![Box::new(EQ_DIFF2) as Box<dyn Constraint>].check_all(&graph);
+        assert_eq!(inconsistencies.len(), 1);
+        assert_eq!(inconsistencies[0].triples.len(), 3);
+        let inconsistencies = vec![Box::new(EQ_DIFF3) as Box<dyn Constraint>].check_all(&graph);
+        assert!(inconsistencies.is_empty());
+    }
+
+    #[test]
+    fn cax_adc() {
+        let rep = r#"
+            :x a owl:AllDisjointClasses . :x owl:members (:human :robot :dog) .
+            :Bart a :human, :robot . :Lisa a :human, :student .
+        "#;
+        assert_eq!(check(Box::new(CAX_ADC), rep), vec![4]);
+    }
+
+    #[test]
+    fn prp_asyp() {
+        let rep = r#"
+            :parent a owl:AsymmetricProperty .
+            :Bart :parent :Homer . :Homer :parent :Bart . :Lisa :parent :Marge .
+            :Lisa :knows :Maggie . :Maggie :knows :Lisa .
+        "#;
+        assert_eq!(check(Box::new(PRP_ASYP), rep), vec![3]);
+    }
+
+    #[test]
+    fn prp_adp() {
+        let rep = r#"
+            :x a owl:AllDisjointProperties . :x owl:members (:parent :child :friend) .
+            :Bart :parent :Homer . :Bart :friend :Homer .
+            :Lisa :parent :Marge . :Marge :child :Lisa .
+        "#;
+        assert_eq!(check(Box::new(PRP_ADP), rep), vec![4]);
+    }
+}
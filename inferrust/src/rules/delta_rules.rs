@@ -0,0 +1,380 @@
+//! Class delta groups the following rules :
+//! <ul>
+//! <li>PRP-FP</li>
+//! <li>PRP-IFP</li>
+//! </ul>
+//!
+//! All these rules have the following properties :
+//! <ol>
+//! <li>the head contains the type of a property, and two triples using this property</li>
+//! <li>Equality between both subjects or both objects</li>
+//! <li>Inferred triple is a owl:sameAs between the remaining terms</li>
+//! </ol>
+//!
+//! The owl:sameAs triples are not inferred for every pair of terms,
+//! but only between the first term and each of the others,
+//! the cliques being merged afterwards by `NodeDictionary::merge_same_as`.
+//! Literals are never linked, as they cannot be merged:
+//! two literals of distinct values in the same group are reported by the constraint
+//! of the same name instead
+//! (see `NodeDictionary::distinct_literals` for the values which are compared).
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::{instances_of, with_properties};
+use crate::rules::{Constraint, Inconsistency, Rule};
+
+/// Return the triple of property `p` from which `pair` comes,
+/// given the order of the pairs (see [`apply_delta_rule`](fn.apply_delta_rule.html))
+fn premise(pair: &[u64; 2], p: u64, order: usize) -> [u64; 3] {
+    match order {
+        0 => [pair[0], p, pair[1]],
+        _ => [pair[1], p, pair[0]],
+    }
+}
+
+/// Call `f` on each group of pairs sharing their first element in the sorted slice `pairs`
+fn for_each_group<F>(pairs: &[[u64; 2]], mut f: F)
+where
+    F: FnMut(&[[u64; 2]]),
+{
+    let mut first = 0;
+    for i in 1..=pairs.len() {
+        if i == pairs.len() || pairs[i][0] != pairs[first][0] {
+            f(&pairs[first..i]);
+            first = i;
+        }
+    }
+}
+
+/// Infer that the first value of `values` which is not a literal is `owl:sameAs` each of the others
+///
+/// Each value comes with the premises it is inferred from,
+/// which are used after the premises `common` to all of them.
+/// Literals are skipped, as they cannot be merged.
+pub fn link_same_as<I, P>(
+    graph: &InfGraph,
+    common: &[[u64; 3]],
+    values: I,
+    output: &mut TripleStore,
+) where
+    I: IntoIterator<Item = (u64, P)>,
+    P: AsRef<[[u64; 3]]>,
+{
+    let same_as = graph.dictionary.owlsameAs as u64;
+    let mut values = values
+        .into_iter()
+        .filter(|(y, _)| !graph.dictionary.is_literal(*y));
+    if let Some((first, first_premises)) = values.next() {
+        for (y, premises) in values {
+            if y == first {
+                continue;
+            }
+            if output.records_premises() {
+                let mut all = common.to_vec();
+                all.extend_from_slice(first_premises.as_ref());
+                all.extend_from_slice(premises.as_ref());
+                output.add_inferred_triple([first, same_as, y], &all);
+            } else {
+                output.add_triple([first, same_as, y]);
+            }
+        }
+    }
+}
+
+/// General method to apply a rule of the delta class, given the type of the properties,
+/// and the order of the pairs (0 to group them by subject, 1 to group them by object)
+///
+/// If `delta` is given, only the groups gaining a pair in `delta` are linked again,
+/// unless the property has just been given its type.
+pub fn apply_delta_rule(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    prop_type: u64,
+    order: usize,
+) -> TripleStore {
+    let mut output = graph.new_output();
+    let ts = &graph.dictionary.ts;
+    let rdftype = graph.dictionary.rdftype as u64;
+    let id = NodeDictionary::prop_idx_to_idx(rdftype);
+    for pair in ts.pairs_with(id, 1, prop_type) {
+        if !NodeDictionary::is_property(pair[1]) {
+            continue;
+        }
+        let prop_idx = NodeDictionary::prop_idx_to_idx(pair[1]);
+        let p = pair[1];
+        let link_group = |pairs: &[[u64; 2]], output: &mut TripleStore| {
+            let values = pairs
+                .iter()
+                .map(|pair| (pair[1], [premise(pair, p, order)]));
+            link_same_as(graph, &[[p, rdftype, prop_type]], values, output);
+        };
+        match delta {
+            Some(delta) if !delta.contains_pair(id, [pair[1], prop_type]) => {
+                let mut last = None;
+                for delta_pair in delta.pairs(prop_idx, order) {
+                    if last != Some(delta_pair[0]) {
+                        last = Some(delta_pair[0]);
+                        link_group(ts.pairs_with(prop_idx, order, delta_pair[0]), &mut output);
+                    }
+                }
+            }
+            _ => for_each_group(ts.pairs(prop_idx, order), |group| {
+                link_group(group, &mut output)
+            }),
+        }
+    }
+    output
+}
+
+/// General method to check a constraint of the delta class, given the type of the properties,
+/// and the order of the pairs (see [`apply_delta_rule`](fn.apply_delta_rule.html))
+///
+/// A group containing two literals known to have distinct values is inconsistent,
+/// as they cannot denote the same value.
+fn check_delta_literals<C>(
+    constraint: &C,
+    graph: &InfGraph,
+    prop_type: u64,
+    order: usize,
+) -> Vec<Inconsistency>
+where
+    C: Constraint + ?Sized,
+{
+    let dictionary = &graph.dictionary;
+    let ts = &dictionary.ts;
+    let rdftype = dictionary.rdftype as u64;
+    let id = NodeDictionary::prop_idx_to_idx(rdftype);
+    let mut inconsistencies = Vec::new();
+    for pair in ts.pairs_with(id, 1, prop_type) {
+        if !NodeDictionary::is_property(pair[1]) {
+            continue;
+        }
+        let p = pair[1];
+        for_each_group(
+            ts.pairs(NodeDictionary::prop_idx_to_idx(p), order),
+            |group| {
+                let mut literals = group.iter().filter(|pair| dictionary.is_literal(pair[1]));
+                if let Some(first) = literals.next() {
+                    for pair in
+                        literals.filter(|pair| dictionary.distinct_literals(first[1], pair[1]))
+                    {
+                        inconsistencies.push(constraint.violation(vec![
+                            [p, rdftype, prop_type],
+                            premise(first, p, order),
+                            premise(pair, p, order),
+                        ]));
+                    }
+                }
+            },
+        );
+    }
+    inconsistencies
+}
+
+/// The PRP-FP rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdf:type owl:FunctionalProperty
+/// - x p y1
+/// - x p y2
+/// Head:
+/// - y1 owl:sameAs y2
+///
+/// As a constraint, it reports the literals y1 and y2 of distinct values.
+pub struct PRP_FP;
+
+impl Rule for PRP_FP {
+    fn name(&self) -> &str {
+        "PRP-FP"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let typed = instances_of(dictionary, dictionary.owlfunctionalProperty as u64);
+        Some(with_properties(vec![dictionary.rdftype], typed))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlsameAs])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let functional = graph.dictionary.owlfunctionalProperty as u64;
+        apply_delta_rule(graph, delta, functional, 0)
+    }
+}
+
+impl Constraint for PRP_FP {
+    fn name(&self) -> &str {
+        "PRP-FP"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let functional = graph.dictionary.owlfunctionalProperty as u64;
+        check_delta_literals(self, graph, functional, 0)
+    }
+}
+
+/// The PRP-IFP rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdf:type owl:InverseFunctionalProperty
+/// - x1 p y
+/// - x2 p y
+/// Head:
+/// - x1 owl:sameAs x2
+///
+/// As a constraint, it reports the literals x1 and x2 of distinct values.
+pub struct PRP_IFP;
+
+impl Rule for PRP_IFP {
+    fn name(&self) -> &str {
+        "PRP-IFP"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let typed = instances_of(dictionary, dictionary.owlinverseFunctionalProperty as u64);
+        Some(with_properties(vec![dictionary.rdftype], typed))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.owlsameAs])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let inverse_functional = graph.dictionary.owlinverseFunctionalProperty as u64;
+        apply_delta_rule(graph, delta, inverse_functional, 1)
+    }
+}
+
+impl Constraint for PRP_IFP {
+    fn name(&self) -> &str {
+        "PRP-IFP"
+    }
+
+    fn check(&self, graph: &InfGraph) -> Vec<Inconsistency> {
+        let inverse_functional = graph.dictionary.owlinverseFunctionalProperty as u64;
+        check_delta_literals(self, graph, inverse_functional, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn prp_fp() {
+        let graph = saturate(
+            r#"
+            :mother a owl:FunctionalProperty .
+            :Bart :mother :Marge, :MargeSimpson .
+            :Bart :friend :Milhouse, :Nelson .
+            "#,
+            vec![Box::new(PRP_FP)],
+        );
+        assert_eq!(index(&graph, ":Marge"), index(&graph, ":MargeSimpson"));
+        assert_ne!(index(&graph, ":Milhouse"), index(&graph, ":Nelson"));
+    }
+
+    #[test]
+    fn prp_fp_literals() {
+        let graph = saturate(
+            r#"
+            :age a owl:FunctionalProperty .
+            :Bart :age "10", "ten", :ten .
+            :Lisa :age "8" .
+            "#,
+            vec![Box::new(PRP_FP)],
+        );
+        assert!(graph
+            .dictionary
+            .ts
+            .pairs(
+                NodeDictionary::prop_idx_to_idx(graph.dictionary.owlsameAs as u64),
+                0
+            )
+            .is_empty());
+        let inconsistencies = Constraint::check(&PRP_FP, &graph);
+        assert_eq!(inconsistencies.len(), 1);
+        assert_eq!(inconsistencies[0].rule, "PRP-FP");
+        assert_eq!(inconsistencies[0].triples.len(), 3);
+    }
+
+    #[test]
+    fn prp_fp_equal_literals() {
+        let graph = graph(
+            r#"
+            :age a owl:FunctionalProperty .
+            :Bart :age "10"^^xsd:integer .
+            :Bart :age "010"^^xsd:integer .
+            :Lisa :age "8"^^xsd:integer .
+            :Lisa :age "8" .
+            "#,
+        );
+        // the literals of Bart have the same value, unlike those of Lisa
+        let inconsistencies = Constraint::check(&PRP_FP, &graph);
+        assert_eq!(inconsistencies.len(), 1);
+        let lisa = index(&graph, ":Lisa").unwrap();
+        assert!(inconsistencies[0].triples.iter().any(|t| t[0] == lisa));
+    }
+
+    #[test]
+    fn prp_ifp() {
+        let graph = saturate(
+            r#"
+            :email a owl:InverseFunctionalProperty .
+            :Homer :email :chunkylover53 .
+            :HomerSimpson :email :chunkylover53 .
+            :Bart :friend :Milhouse . :Lisa :friend :Milhouse .
+            "#,
+            vec![Box::new(PRP_IFP)],
+        );
+        assert_eq!(index(&graph, ":Homer"), index(&graph, ":HomerSimpson"));
+        assert_ne!(index(&graph, ":Bart"), index(&graph, ":Lisa"));
+    }
+
+    #[test]
+    fn prp_fp_delta() {
+        let graph = graph(
+            r#"
+            :mother a owl:FunctionalProperty .
+            :Bart :mother :Marge, :MargeSimpson, :MrsSimpson .
+            :Lisa :mother :Marge .
+            "#,
+        );
+        // the first value is linked to each of the others
+        let mut output = fire(&graph, &mut PRP_FP, None);
+        assert_eq!(output.size(), 2);
+        // the group of Lisa has a single value
+        let delta = [[":Lisa", ":mother", ":Marge"]];
+        assert!(fire(&graph, &mut PRP_FP, Some(&delta)).is_empty());
+        let delta = [[":Bart", ":mother", ":MrsSimpson"]];
+        let mut output = fire(&graph, &mut PRP_FP, Some(&delta));
+        assert_eq!(output.size(), 2);
+    }
+
+    #[test]
+    fn prp_ifp_new_type() {
+        let graph = graph(
+            r#"
+            :email a owl:InverseFunctionalProperty .
+            :Homer :email :chunkylover53 .
+            :HomerSimpson :email :chunkylover53 .
+            "#,
+        );
+        // the property is not functional
+        let delta = [[":Homer", ":email", ":chunkylover53"]];
+        assert!(fire(&graph, &mut PRP_FP, Some(&delta)).is_empty());
+        // a newly typed property links all its groups
+        let delta = [[":email", "rdf:type", "owl:InverseFunctionalProperty"]];
+        let output = fire(&graph, &mut PRP_IFP, Some(&delta));
+        assert!(
+            contains(&graph, &output, ":Homer", "owl:sameAs", ":HomerSimpson")
+                || contains(&graph, &output, ":HomerSimpson", "owl:sameAs", ":Homer")
+        );
+    }
+}
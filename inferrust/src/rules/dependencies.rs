@@ -0,0 +1,151 @@
+//! The dependencies between the rules and the predicates they read
+
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::Rule;
+
+/// The dependency graph between the chunks of the [`TripleStore`](../inferray/struct.TripleStore.html)
+/// and the rules reading them, built from [`Rule::input_predicates`](trait.Rule.html#method.input_predicates)
+///
+/// It is used by [`RuleSet::fire_all`](trait.RuleSet.html#tymethod.fire_all)
+/// to fire again only the rules whose input chunks gained triples.
+/// Built from [`Rule::output_predicates`](trait.Rule.html#method.output_predicates) instead,
+/// it links the chunks to the rules writing them.
+/// As the inputs of some rules depend on the schema (e.g. the properties typed as transitive),
+/// it must be built again when the store changes.
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+///
+///     :human rdfs:subClassOf :mammal .
+/// "#;
+/// let graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let rules: Vec<Box<dyn Rule>> = vec![Box::new(CAX_SCO), Box::new(SCM_SCO)];
+/// let dependencies = DependencyGraph::new(&rules, &graph.dictionary);
+///
+/// // a new rdf:type triple only concerns CAX-SCO
+/// let mut delta = TripleStore::new();
+/// let resource = graph.dictionary.rdfsResource;
+/// delta.add_triple([resource, graph.dictionary.rdftype as u64, resource]);
+/// assert_eq!(dependencies.triggered(&delta), vec![true, false]);
+/// ```
+pub struct DependencyGraph {
+    /// the rules reading (or writing) each chunk, indexed as `TripleStore::elem`
+    rules: Vec<Vec<usize>>,
+    /// the rules reading (or writing) any predicate
+    any: Vec<usize>,
+    /// the number of rules
+    len: usize,
+}
+
+impl DependencyGraph {
+    /// Link the chunks to the rules reading them
+    pub fn new(rules: &[Box<dyn Rule>], dictionary: &NodeDictionary) -> Self {
+        Self::build(rules, |rule| rule.input_predicates(dictionary))
+    }
+
+    /// Link the chunks to the rules writing them
+    pub fn from_outputs(rules: &[Box<dyn Rule>], dictionary: &NodeDictionary) -> Self {
+        Self::build(rules, |rule| rule.output_predicates(dictionary))
+    }
+
+    fn build<F>(rules: &[Box<dyn Rule>], predicates: F) -> Self
+    where
+        F: Fn(&dyn Rule) -> Option<Vec<u32>>,
+    {
+        let mut by_chunk: Vec<Vec<usize>> = Vec::new();
+        let mut any = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            match predicates(rule.as_ref()) {
+                Some(predicates) => {
+                    for p in predicates {
+                        let idx = NodeDictionary::prop_idx_to_idx(p as u64);
+                        if idx >= by_chunk.len() {
+                            by_chunk.resize_with(idx + 1, Default::default);
+                        }
+                        if by_chunk[idx].last() != Some(&i) {
+                            by_chunk[idx].push(i);
+                        }
+                    }
+                }
+                None => any.push(i),
+            }
+        }
+        Self {
+            rules: by_chunk,
+            any,
+            len: rules.len(),
+        }
+    }
+
+    /// Return, for each rule, whether it reads (or writes) a chunk having triples in `delta`
+    pub fn triggered(&self, delta: &TripleStore) -> Vec<bool> {
+        let mut triggered = vec![false; self.len];
+        let mut changed = false;
+        for (idx, chunk) in delta.elem.iter().enumerate() {
+            if chunk[0].is_empty() {
+                continue;
+            }
+            changed = true;
+            if let Some(rules) = self.rules.get(idx) {
+                for &i in rules {
+                    triggered[i] = true;
+                }
+            }
+        }
+        if changed {
+            for &i in &self.any {
+                triggered[i] = true;
+            }
+        }
+        triggered
+    }
+}
+
+/// Return `predicates`, followed by the properties found among `candidates`
+///
+/// This helps rules whose inputs or outputs depend on the schema to declare them.
+pub fn with_properties<I>(mut predicates: Vec<u32>, candidates: I) -> Vec<u32>
+where
+    I: IntoIterator<Item = u64>,
+{
+    for candidate in candidates {
+        if NodeDictionary::is_property(candidate) {
+            predicates.push(candidate as u32);
+        }
+    }
+    predicates.sort_unstable();
+    predicates.dedup();
+    predicates
+}
+
+/// Return `true` if `delta` is `None`, or contains triples having one of `predicates`
+///
+/// This helps rules which must be fully applied again when the schema they use has changed.
+pub fn touches(delta: Option<&TripleStore>, predicates: &[u32]) -> bool {
+    match delta {
+        None => true,
+        Some(delta) => predicates.iter().any(|&p| {
+            !delta
+                .pairs(NodeDictionary::prop_idx_to_idx(p as u64), 0)
+                .is_empty()
+        }),
+    }
+}
+
+/// Return the indexes typed as `class` in the store of `dictionary`
+pub fn instances_of(dictionary: &NodeDictionary, class: u64) -> impl Iterator<Item = u64> + '_ {
+    let id = NodeDictionary::prop_idx_to_idx(dictionary.rdftype as u64);
+    dictionary
+        .ts
+        .pairs_with(id, 1, class)
+        .iter()
+        .map(|pair| pair[1])
+}
@@ -1,9 +1,11 @@
+#![allow(non_camel_case_types)]
+
 /**
  * The Epsilon class covers :
  * <ul>
  * <li>PRP-INV1/2</li>
  * <li>PRP-EQP1/2</li>
- * <li></li>
+ * <li>PRP-SYMP</li>
  * </ul>
  *
  * These rules have the following template
@@ -26,51 +28,249 @@ use crate::inferray::InfGraph;
 use crate::inferray::NodeDictionary;
 use crate::inferray::TripleStore;
 use crate::rules::Rule;
+use crate::rules::{instances_of, with_properties};
 
-fn apply_epsilon_rule(graph: &InfGraph, prop_idx: usize, invert: bool) -> TripleStore {
-    let mut output = TripleStore::new();
-    if let Some(pairs) = graph.dictionary.ts.elem.get(prop_idx) {
-        for pair in &pairs[0] {
+fn apply_epsilon_rule(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    prop_idx: usize,
+    invert: bool,
+) -> TripleStore {
+    let ts = &graph.dictionary.ts;
+    let prop = NodeDictionary::idx_to_prop_idx(prop_idx);
+    let mut output = graph.new_output();
+    for pair in delta.unwrap_or(ts).pairs(prop_idx, 0) {
+        if pair[0] != pair[1] {
+            let schema = [pair[0], prop, pair[1]];
+            copy_pairs(ts, schema, pair[0], pair[1], invert, &mut output);
+            copy_pairs(ts, schema, pair[1], pair[0], invert, &mut output);
+        }
+    }
+    if let Some(delta) = delta {
+        for pair in ts.pairs(prop_idx, 0) {
             if pair[0] != pair[1] {
-                // dbg!(pair);
-                // dbg!(graph.dictionary.get_term(pair[0]));
-                // dbg!(graph.dictionary.get_term(pair[1]));
-                let prop_idx = NodeDictionary::prop_idx_to_idx(pair[0]);
-                if let Some(usable_pairs) = graph.dictionary.ts.elem.get(prop_idx) {
-                    for usable_pair in &usable_pairs[if invert { 1 } else { 0 }] {
-                        // dbg!(usable_pair);
-                        // dbg!(graph.dictionary.get_term(usable_pair[0]));
-                        // dbg!(graph.dictionary.get_term(usable_pair[1]));
-                        output.add_triple([usable_pair[0], pair[1], usable_pair[1]]);
-                    }
-                }
-                let prop_idx = NodeDictionary::prop_idx_to_idx(pair[1]);
-                if let Some(usable_pairs) = graph.dictionary.ts.elem.get(prop_idx) {
-                    for usable_pair in &usable_pairs[if invert { 1 } else { 0 }] {
-                        // dbg!(usable_pair);
-                        // dbg!(graph.dictionary.get_term(usable_pair[0]));
-                        // dbg!(graph.dictionary.get_term(usable_pair[1]));
-                        output.add_triple([usable_pair[0], pair[0], usable_pair[1]]);
-                    }
-                }
+                let schema = [pair[0], prop, pair[1]];
+                copy_pairs(delta, schema, pair[0], pair[1], invert, &mut output);
+                copy_pairs(delta, schema, pair[1], pair[0], invert, &mut output);
             }
         }
     }
     output
 }
 
-pub fn PRP_INV_1_2(graph: &mut InfGraph) -> TripleStore {
-    apply_epsilon_rule(
-        graph,
-        NodeDictionary::prop_idx_to_idx(graph.dictionary.owlinverseOf as u64),
-        true,
-    )
+/// Infer a triple with property `to` for each pair of property `from` in `store`,
+/// swapping subject and object if `invert` is true,
+/// from the `schema` triple linking both properties
+fn copy_pairs(
+    store: &TripleStore,
+    schema: [u64; 3],
+    from: u64,
+    to: u64,
+    invert: bool,
+    output: &mut TripleStore,
+) {
+    let prop_idx = NodeDictionary::prop_idx_to_idx(from);
+    for usable_pair in store.pairs(prop_idx, if invert { 1 } else { 0 }) {
+        let premise = if invert {
+            [usable_pair[1], from, usable_pair[0]]
+        } else {
+            [usable_pair[0], from, usable_pair[1]]
+        };
+        output.add_inferred_triple([usable_pair[0], to, usable_pair[1]], &[schema, premise]);
+    }
 }
 
-pub fn PRP_EQP_1_2(graph: &mut InfGraph) -> TripleStore {
-    apply_epsilon_rule(
-        graph,
-        NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentProperty as u64),
-        false,
-    )
+pub struct PRP_INV_1_2;
+
+impl Rule for PRP_INV_1_2 {
+    fn name(&self) -> &str {
+        "PRP-INV1/2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.owlinverseOf as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![dictionary.owlinverseOf],
+            pairs.iter().flat_map(|pair| pair.iter().cloned()),
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.owlinverseOf as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![],
+            pairs.iter().flat_map(|pair| pair.iter().cloned()),
+        ))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        apply_epsilon_rule(
+            graph,
+            delta,
+            NodeDictionary::prop_idx_to_idx(graph.dictionary.owlinverseOf as u64),
+            true,
+        )
+    }
+}
+
+pub struct PRP_EQP_1_2;
+
+impl Rule for PRP_EQP_1_2 {
+    fn name(&self) -> &str {
+        "PRP-EQP1/2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.owlequivalentProperty as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![dictionary.owlequivalentProperty],
+            pairs.iter().flat_map(|pair| pair.iter().cloned()),
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.owlequivalentProperty as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![],
+            pairs.iter().flat_map(|pair| pair.iter().cloned()),
+        ))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        apply_epsilon_rule(
+            graph,
+            delta,
+            NodeDictionary::prop_idx_to_idx(graph.dictionary.owlequivalentProperty as u64),
+            false,
+        )
+    }
+}
+
+pub struct PRP_SYMP;
+
+impl Rule for PRP_SYMP {
+    fn name(&self) -> &str {
+        "PRP-SYMP"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let typed = instances_of(dictionary, dictionary.owlsymetricProperty as u64);
+        Some(with_properties(vec![dictionary.rdftype], typed))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let typed = instances_of(dictionary, dictionary.owlsymetricProperty as u64);
+        Some(with_properties(vec![], typed))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let mut output = graph.new_output();
+        let rdftype = graph.dictionary.rdftype as u64;
+        let id = NodeDictionary::prop_idx_to_idx(rdftype);
+        let symmetric = graph.dictionary.owlsymetricProperty as u64;
+        let ts = &graph.dictionary.ts;
+        for pair in ts.pairs_with(id, 1, symmetric) {
+            if NodeDictionary::is_property(pair[1]) {
+                let store = match delta {
+                    Some(delta) if !delta.contains_pair(id, [pair[1], symmetric]) => delta,
+                    _ => ts,
+                };
+                let schema = [pair[1], rdftype, symmetric];
+                copy_pairs(store, schema, pair[1], pair[1], true, &mut output);
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn prp_inv() {
+        let graph = saturate(
+            ":hasChild owl:inverseOf :hasParent . :Homer :hasChild :Bart . :Lisa :hasParent :Marge .",
+            vec![Box::new(PRP_INV_1_2)],
+        );
+        assert!(has(&graph, ":Bart", ":hasParent", ":Homer"));
+        assert!(has(&graph, ":Marge", ":hasChild", ":Lisa"));
+        assert!(!has(&graph, ":Homer", ":hasParent", ":Bart"));
+        // both properties of the axiom are read by the rule
+        let predicates = PRP_INV_1_2.input_predicates(&graph.dictionary).unwrap();
+        for name in &[":hasChild", ":hasParent"] {
+            let p = index(&graph, name).unwrap() as u32;
+            assert!(predicates.contains(&p));
+        }
+    }
+
+    #[test]
+    fn prp_inv_delta() {
+        let graph = graph(
+            r#"
+            :hasChild owl:inverseOf :hasParent .
+            :Homer :hasChild :Bart .
+            :Homer :hasChild :Lisa .
+            :Lisa :hasParent :Marge .
+            "#,
+        );
+        let delta = [[":Homer", ":hasChild", ":Lisa"]];
+        let output = fire(&graph, &mut PRP_INV_1_2, Some(&delta));
+        assert!(contains(&graph, &output, ":Lisa", ":hasParent", ":Homer"));
+        assert!(!contains(&graph, &output, ":Bart", ":hasParent", ":Homer"));
+        assert!(!contains(&graph, &output, ":Marge", ":hasChild", ":Lisa"));
+    }
+
+    #[test]
+    fn prp_eqp() {
+        let graph = saturate_with_provenance(
+            ":hasPet owl:equivalentProperty :owns . :Bart :hasPet :SantasLittleHelper . :Lisa :owns :Snowball .",
+            vec![Box::new(PRP_EQP_1_2)],
+        );
+        assert!(has(&graph, ":Lisa", ":hasPet", ":Snowball"));
+        assert!(!has(&graph, ":SantasLittleHelper", ":owns", ":Bart"));
+        let derivation = graph
+            .explain(&iri(":Bart"), &iri(":owns"), &iri(":SantasLittleHelper"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("PRP-EQP1/2"));
+        assert_eq!(derivation.premises.len(), 2);
+        assert_eq!(
+            derivation.premises[1].triple[1].value(),
+            iri(":hasPet").value()
+        );
+    }
+
+    #[test]
+    fn prp_symp() {
+        let graph = graph(
+            r#"
+            :sibling a owl:SymmetricProperty .
+            :Bart :sibling :Lisa . :Lisa :sibling :Maggie .
+            :Bart :knows :Milhouse .
+            "#,
+        );
+        let delta = [[":Lisa", ":sibling", ":Maggie"]];
+        let output = fire(&graph, &mut PRP_SYMP, Some(&delta));
+        assert!(contains(&graph, &output, ":Maggie", ":sibling", ":Lisa"));
+        assert!(!contains(&graph, &output, ":Lisa", ":sibling", ":Bart"));
+        // a newly symmetric property applies to all its pairs
+        let delta = [[":sibling", "rdf:type", "owl:SymmetricProperty"]];
+        let output = fire(&graph, &mut PRP_SYMP, Some(&delta));
+        assert!(contains(&graph, &output, ":Lisa", ":sibling", ":Bart"));
+        assert!(contains(&graph, &output, ":Maggie", ":sibling", ":Lisa"));
+        assert!(!contains(&graph, &output, ":Milhouse", ":knows", ":Bart"));
+    }
 }
@@ -0,0 +1,279 @@
+//! Class gamma groups the following rules :
+//! <ul>
+//! <li>PRP-DOM</li>
+//! <li>PRP-RNG</li>
+//! <li>PRP-SPO1</li>
+//! </ul>
+//!
+//! All these rules have the following properties :
+//! <ol>
+//! <li>1 fixed predicate in the first head triple, whose subject is a property</li>
+//! <li>the predicate of the second head triple is the subject of the first one</li>
+//! <li>Inferred triple contains the object of the first triple
+//! and the subject or object of the second one</li>
+//! </ol>
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::with_properties;
+use crate::rules::Rule;
+
+// :enfant rdfs:domain :human ||| :bart :enfant :lisa
+//    p          rule       v       x      p       y
+//                        -->
+//              head(p, v, x, y)
+
+/// General method to apply a rule of the gamma class, given the index of the rule property
+///
+/// Every property chunk of the store is scanned,
+/// and for each pair `[x, y]` of property `p`,
+/// and for each pair `[p, v]` of the rule property,
+/// the triple `head(p, v, x, y)` is inferred.
+///
+/// If `delta` is given, only the chunks of `delta` are scanned,
+/// and the new pairs of the rule property are applied to the whole store.
+pub fn apply_gamma_rule<F>(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    rule_p: usize,
+    head: F,
+) -> TripleStore
+where
+    F: Fn(u64, u64, u64, u64) -> [u64; 3],
+{
+    let ts = &graph.dictionary.ts;
+    let rule_prop = NodeDictionary::idx_to_prop_idx(rule_p);
+    let infer = |p: u64, rule_pair: &[u64; 2], pair: &[u64; 2], output: &mut TripleStore| {
+        output.add_inferred_triple(
+            head(p, rule_pair[1], pair[0], pair[1]),
+            &[[p, rule_prop, rule_pair[1]], [pair[0], p, pair[1]]],
+        );
+    };
+    let mut output = graph.new_output();
+    if ts.pairs(rule_p, 0).is_empty() {
+        return output;
+    }
+    for (idx, chunk) in delta.unwrap_or(ts).elem.iter().enumerate() {
+        if chunk[0].is_empty() {
+            continue;
+        }
+        let p = NodeDictionary::idx_to_prop_idx(idx);
+        for rule_pair in ts.pairs_with(rule_p, 0, p) {
+            for pair in &chunk[0] {
+                infer(p, rule_pair, pair, &mut output);
+            }
+        }
+    }
+    if let Some(delta) = delta {
+        for rule_pair in delta.pairs(rule_p, 0) {
+            let p = rule_pair[0];
+            if !NodeDictionary::is_property(p) {
+                continue;
+            }
+            for pair in ts.pairs(NodeDictionary::prop_idx_to_idx(p), 0) {
+                infer(p, rule_pair, pair, &mut output);
+            }
+        }
+    }
+    output
+}
+
+/// The PRP-DOM rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdfs:domain c
+/// - x p y
+/// Head:
+/// - x rdf:type c
+pub struct PRP_DOM;
+
+impl Rule for PRP_DOM {
+    fn name(&self) -> &str {
+        "PRP-DOM"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.rdfsdomain as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![dictionary.rdfsdomain],
+            pairs.iter().map(|pair| pair[0]),
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsdomain as u64);
+        let rdftype = graph.dictionary.rdftype as u64;
+        apply_gamma_rule(graph, delta, id, |_, c, x, _| [x, rdftype, c])
+    }
+}
+
+/// The PRP-RNG rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p rdfs:range c
+/// - x p y
+/// Head:
+/// - y rdf:type c
+pub struct PRP_RNG;
+
+impl Rule for PRP_RNG {
+    fn name(&self) -> &str {
+        "PRP-RNG"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.rdfsrange as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![dictionary.rdfsrange],
+            pairs.iter().map(|pair| pair[0]),
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsrange as u64);
+        let rdftype = graph.dictionary.rdftype as u64;
+        apply_gamma_rule(graph, delta, id, |_, c, _, y| [y, rdftype, c])
+    }
+}
+
+/// The PRP-SPO1 rule from the RDFS+ ruleset
+///
+/// Body:
+/// - p1 rdfs:subPropertyOf p2
+/// - x p1 y
+/// Head:
+/// - x p2 y
+pub struct PRP_SPO1;
+
+impl Rule for PRP_SPO1 {
+    fn name(&self) -> &str {
+        "PRP-SPO1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.rdfssubPropertyOf as u64),
+            0,
+        );
+        Some(with_properties(
+            vec![dictionary.rdfssubPropertyOf],
+            pairs.iter().map(|pair| pair[0]),
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let pairs = dictionary.ts.pairs(
+            NodeDictionary::prop_idx_to_idx(dictionary.rdfssubPropertyOf as u64),
+            0,
+        );
+        Some(with_properties(vec![], pairs.iter().map(|pair| pair[1])))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let id = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfssubPropertyOf as u64);
+        apply_gamma_rule(graph, delta, id, |_, p2, x, y| [x, p2, y])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn prp_dom() {
+        let graph = saturate_with_provenance(
+            ":hasPet rdfs:domain :human . :Bart :hasPet :SantasLittleHelper . :Lisa :knows :Bart .",
+            vec![Box::new(PRP_DOM)],
+        );
+        assert!(has(&graph, ":Bart", "rdf:type", ":human"));
+        assert!(!has(&graph, ":Lisa", "rdf:type", ":human"));
+        let derivation = graph
+            .explain(&iri(":Bart"), &iri("rdf:type"), &iri(":human"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("PRP-DOM"));
+        assert_eq!(derivation.premises.len(), 2);
+        assert!(derivation.premises.iter().all(|p| p.rule.is_none()));
+    }
+
+    #[test]
+    fn prp_rng_delta() {
+        let graph = graph(
+            r#"
+            :hasPet rdfs:range :pet .
+            :Bart :hasPet :SantasLittleHelper .
+            :Lisa :hasPet :Snowball .
+            :Lisa :knows :Bart .
+            "#,
+        );
+        // a new pair of a property with a range
+        let delta = [[":Lisa", ":hasPet", ":Snowball"]];
+        let output = fire(&graph, &mut PRP_RNG, Some(&delta));
+        assert!(contains(&graph, &output, ":Snowball", "rdf:type", ":pet"));
+        assert!(!contains(
+            &graph,
+            &output,
+            ":SantasLittleHelper",
+            "rdf:type",
+            ":pet"
+        ));
+        // a new range applies to all the pairs of the property
+        let delta = [[":hasPet", "rdfs:range", ":pet"]];
+        let output = fire(&graph, &mut PRP_RNG, Some(&delta));
+        assert!(contains(
+            &graph,
+            &output,
+            ":SantasLittleHelper",
+            "rdf:type",
+            ":pet"
+        ));
+        assert!(contains(&graph, &output, ":Snowball", "rdf:type", ":pet"));
+        // a pair of a property without range infers nothing
+        let delta = [[":Lisa", ":knows", ":Bart"]];
+        assert!(fire(&graph, &mut PRP_RNG, Some(&delta)).is_empty());
+    }
+
+    #[test]
+    fn prp_spo1() {
+        let graph = saturate(
+            r#"
+            :hasDog rdfs:subPropertyOf :hasPet .
+            :hasPet rdfs:subPropertyOf :owns .
+            :Bart :hasDog :SantasLittleHelper .
+            :Lisa :hasPet :Snowball .
+            "#,
+            vec![Box::new(PRP_SPO1)],
+        );
+        // the rule is fired again on its own output
+        assert!(has(&graph, ":Bart", ":owns", ":SantasLittleHelper"));
+        assert!(has(&graph, ":Lisa", ":owns", ":Snowball"));
+        assert!(!has(&graph, ":Lisa", ":hasDog", ":Snowball"));
+    }
+
+    #[test]
+    fn no_rule_pair() {
+        let rep = ":Bart :hasPet :SantasLittleHelper .";
+        let mut graph = graph(rep);
+        assert!(fire(&graph, &mut PRP_DOM, None).is_empty());
+        assert!(fire(&graph, &mut PRP_SPO1, None).is_empty());
+        let size = graph.size();
+        assert_eq!(saturate(rep, vec![Box::new(PRP_DOM)]).size(), size);
+    }
+}
@@ -0,0 +1,437 @@
+//! The list class groups the following rules :
+//! <ul>
+//! <li>CLS-INT1</li>
+//! <li>CLS-INT2</li>
+//! <li>CLS-UNI</li>
+//! <li>SCM-INT</li>
+//! <li>SCM-UNI</li>
+//! </ul>
+//!
+//! All these rules use a class expression `c p l`,
+//! where `p` is `owl:intersectionOf` or `owl:unionOf`,
+//! and `l` is the head of a RDF list of classes `c1 ... cn`.
+//! As the lists are decoded from the store each time a rule is fired,
+//! a `delta` containing a triple of a class expression or of a list
+//! triggers a full application of the rule.
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::touches;
+use crate::rules::Rule;
+
+/// A class expression `c p l`, where `l` is the head of the list of classes `members`
+struct ClassList {
+    class: u64,
+    head: u64,
+    members: Vec<u64>,
+}
+
+/// Return the well-formed class expressions of property `rule_p` found in the store
+fn class_lists(graph: &InfGraph, rule_p: u32) -> Vec<ClassList> {
+    graph
+        .dictionary
+        .ts
+        .pairs(NodeDictionary::prop_idx_to_idx(rule_p as u64), 0)
+        .iter()
+        .filter_map(|pair| {
+            graph.dictionary.list(pair[1]).map(|members| ClassList {
+                class: pair[0],
+                head: pair[1],
+                members,
+            })
+        })
+        .collect()
+}
+
+/// Return `true` if `delta` is `None`, or contains triples of property `rule_p` or of a RDF list
+fn schema_changed(graph: &InfGraph, delta: Option<&TripleStore>, rule_p: u32) -> bool {
+    let dictionary = &graph.dictionary;
+    touches(delta, &[rule_p, dictionary.rdffirst, dictionary.rdfrest])
+}
+
+/// Return the predicates used by a rule on the class expressions of property `rule_p`
+fn list_predicates(dictionary: &NodeDictionary, rule_p: u32, with_type: bool) -> Vec<u32> {
+    let mut predicates = vec![rule_p, dictionary.rdffirst, dictionary.rdfrest];
+    if with_type {
+        predicates.push(dictionary.rdftype);
+    }
+    predicates
+}
+
+/// Return the subjects of the `rdf:type` pairs of `store` whose class is `class`
+fn instances<'a>(graph: &InfGraph, store: &'a TripleStore, class: u64) -> &'a [[u64; 2]] {
+    store.pairs_with(
+        NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64),
+        1,
+        class,
+    )
+}
+
+/// The CLS-INT1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c owl:intersectionOf (c1 ... cn)
+/// - y rdf:type c1 ... y rdf:type cn
+/// Head:
+/// - y rdf:type c
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+/// use sophia::term::RcTerm;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix owl: <http://www.w3.org/2002/07/owl#> .
+///
+///     :Laptop owl:intersectionOf ( :Computer :Portable ) .
+///     :x1 a :Computer, :Portable .
+///     :x2 a :Computer .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::OWL2RL);
+/// rules.fire_all(&mut graph);
+///
+/// let index = |iri| {
+///     let term = RcTerm::new_iri(iri).unwrap();
+///     graph.dictionary.get_index(&term).unwrap()
+/// };
+/// let rdftype = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64);
+/// let laptop = index("http://example.org/Laptop");
+/// assert!(graph.dictionary.ts.contains_pair(rdftype, [index("http://example.org/x1"), laptop]));
+/// assert!(!graph.dictionary.ts.contains_pair(rdftype, [index("http://example.org/x2"), laptop]));
+/// ```
+pub struct CLS_INT1;
+
+impl Rule for CLS_INT1 {
+    fn name(&self) -> &str {
+        "CLS-INT1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(list_predicates(dictionary, dictionary.intersectionOf, true))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let ts = &graph.dictionary.ts;
+        let intersection_of = graph.dictionary.intersectionOf;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+        let full = schema_changed(graph, delta, intersection_of);
+        let mut output = graph.new_output();
+        for list in class_lists(graph, intersection_of) {
+            if list.members.is_empty() {
+                continue;
+            }
+            // the instances of the first class are the candidates,
+            // unless only the instances of `delta` are to be checked
+            let mut candidates: Vec<u64> = match delta {
+                Some(delta) if !full => list
+                    .members
+                    .iter()
+                    .flat_map(|&ci| instances(graph, delta, ci))
+                    .map(|pair| pair[1])
+                    .collect(),
+                _ => instances(graph, ts, list.members[0])
+                    .iter()
+                    .map(|pair| pair[1])
+                    .collect(),
+            };
+            candidates.sort_unstable();
+            candidates.dedup();
+            for y in candidates {
+                if list
+                    .members
+                    .iter()
+                    .all(|&ci| ts.contains_pair(type_idx, [y, ci]))
+                {
+                    let mut premises = vec![[list.class, intersection_of as u64, list.head]];
+                    premises.extend(list.members.iter().map(|&ci| [y, rdftype, ci]));
+                    output.add_inferred_triple([y, rdftype, list.class], &premises);
+                }
+            }
+        }
+        output
+    }
+}
+
+/// The CLS-INT2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c owl:intersectionOf (c1 ... cn)
+/// - y rdf:type c
+/// Head:
+/// - y rdf:type c1 ... y rdf:type cn
+pub struct CLS_INT2;
+
+impl Rule for CLS_INT2 {
+    fn name(&self) -> &str {
+        "CLS-INT2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(list_predicates(dictionary, dictionary.intersectionOf, true))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let intersection_of = graph.dictionary.intersectionOf;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let source = if schema_changed(graph, delta, intersection_of) {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for list in class_lists(graph, intersection_of) {
+            for pair in instances(graph, source, list.class) {
+                let y = pair[1];
+                for &ci in &list.members {
+                    output.add_inferred_triple(
+                        [y, rdftype, ci],
+                        &[
+                            [list.class, intersection_of as u64, list.head],
+                            [y, rdftype, list.class],
+                        ],
+                    );
+                }
+            }
+        }
+        output
+    }
+}
+
+/// The CLS-UNI rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c owl:unionOf (c1 ... cn)
+/// - y rdf:type ci
+/// Head:
+/// - y rdf:type c
+pub struct CLS_UNI;
+
+impl Rule for CLS_UNI {
+    fn name(&self) -> &str {
+        "CLS-UNI"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(list_predicates(dictionary, dictionary.unionOf, true))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let union_of = graph.dictionary.unionOf;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let source = if schema_changed(graph, delta, union_of) {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for list in class_lists(graph, union_of) {
+            for &ci in &list.members {
+                for pair in instances(graph, source, ci) {
+                    let y = pair[1];
+                    output.add_inferred_triple(
+                        [y, rdftype, list.class],
+                        &[[list.class, union_of as u64, list.head], [y, rdftype, ci]],
+                    );
+                }
+            }
+        }
+        output
+    }
+}
+
+/// General method to apply a schema rule of the list class
+///
+/// For each class expression `c rule_p (c1 ... cn)`,
+/// the triple `head(c, ci)` is inferred for each `ci`.
+fn apply_list_schema_rule<F>(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    rule_p: u32,
+    head: F,
+) -> TripleStore
+where
+    F: Fn(u64, u64) -> [u64; 3],
+{
+    let mut output = graph.new_output();
+    if !schema_changed(graph, delta, rule_p) {
+        return output;
+    }
+    for list in class_lists(graph, rule_p) {
+        for &ci in &list.members {
+            output.add_inferred_triple(
+                head(list.class, ci),
+                &[[list.class, rule_p as u64, list.head]],
+            );
+        }
+    }
+    output
+}
+
+/// The SCM-INT rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c owl:intersectionOf (c1 ... cn)
+/// Head:
+/// - c rdfs:subClassOf c1 ... c rdfs:subClassOf cn
+pub struct SCM_INT;
+
+impl Rule for SCM_INT {
+    fn name(&self) -> &str {
+        "SCM-INT"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(list_predicates(
+            dictionary,
+            dictionary.intersectionOf,
+            false,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let sub_class_of = graph.dictionary.rdfssubClassOf as u64;
+        apply_list_schema_rule(graph, delta, graph.dictionary.intersectionOf, |c, ci| {
+            [c, sub_class_of, ci]
+        })
+    }
+}
+
+/// The SCM-UNI rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c owl:unionOf (c1 ... cn)
+/// Head:
+/// - c1 rdfs:subClassOf c ... cn rdfs:subClassOf c
+pub struct SCM_UNI;
+
+impl Rule for SCM_UNI {
+    fn name(&self) -> &str {
+        "SCM-UNI"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(list_predicates(dictionary, dictionary.unionOf, false))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let sub_class_of = graph.dictionary.rdfssubClassOf as u64;
+        apply_list_schema_rule(graph, delta, graph.dictionary.unionOf, |c, ci| {
+            [ci, sub_class_of, c]
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn cls_int1() {
+        let graph = saturate_with_provenance(
+            ":Laptop owl:intersectionOf ( :Computer :Portable ) . :x1 a :Computer, :Portable . :x2 a :Computer .",
+            vec![Box::new(CLS_INT1)],
+        );
+        assert!(has(&graph, ":x1", "rdf:type", ":Laptop"));
+        assert!(!has(&graph, ":x2", "rdf:type", ":Laptop"));
+        // the class expression, and one type per member of the list
+        let derivation = graph
+            .explain(&iri(":x1"), &iri("rdf:type"), &iri(":Laptop"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("CLS-INT1"));
+        assert_eq!(derivation.premises.len(), 3);
+    }
+
+    #[test]
+    fn cls_int1_delta() {
+        let graph = graph(
+            r#"
+            :Laptop owl:intersectionOf ( :Computer :Portable ) .
+            :x1 a :Computer, :Portable .
+            :x2 a :Portable, :Computer .
+            "#,
+        );
+        // only the instances of the delta are checked
+        let delta = [[":x2", "rdf:type", ":Portable"]];
+        let output = fire(&graph, &mut CLS_INT1, Some(&delta));
+        assert!(contains(&graph, &output, ":x2", "rdf:type", ":Laptop"));
+        assert!(!contains(&graph, &output, ":x1", "rdf:type", ":Laptop"));
+        // a new class expression checks all the instances again
+        let delta = [[":Laptop", "owl:intersectionOf", ":Computer"]];
+        let output = fire(&graph, &mut CLS_INT1, Some(&delta));
+        assert!(contains(&graph, &output, ":x1", "rdf:type", ":Laptop"));
+    }
+
+    #[test]
+    fn cls_int2() {
+        let graph = saturate(
+            ":Laptop owl:intersectionOf ( :Computer :Portable ) . :x1 a :Laptop . :x2 a :Computer .",
+            vec![Box::new(CLS_INT2)],
+        );
+        assert!(has(&graph, ":x1", "rdf:type", ":Computer"));
+        assert!(has(&graph, ":x1", "rdf:type", ":Portable"));
+        assert!(!has(&graph, ":x2", "rdf:type", ":Portable"));
+    }
+
+    #[test]
+    fn cls_uni() {
+        let graph = saturate(
+            ":Parent owl:unionOf ( :Mother :Father ) . :Homer a :Father . :Bart a :Child .",
+            vec![Box::new(CLS_UNI)],
+        );
+        assert!(has(&graph, ":Homer", "rdf:type", ":Parent"));
+        assert!(!has(&graph, ":Bart", "rdf:type", ":Parent"));
+    }
+
+    #[test]
+    fn scm_int_uni() {
+        let rep = ":Laptop owl:intersectionOf ( :Computer :Portable ) . :Parent owl:unionOf ( :Mother :Father ) .";
+        let graph = saturate(rep, vec![Box::new(SCM_INT)]);
+        assert!(has(&graph, ":Laptop", "rdfs:subClassOf", ":Portable"));
+        assert!(!has(&graph, ":Mother", "rdfs:subClassOf", ":Parent"));
+        let graph = saturate(rep, vec![Box::new(SCM_UNI)]);
+        assert!(has(&graph, ":Mother", "rdfs:subClassOf", ":Parent"));
+        assert!(!has(&graph, ":Laptop", "rdfs:subClassOf", ":Portable"));
+        // the schema rules only depend on the lists
+        let graph = crate::test_utils::graph(&format!("{} :x1 a :Mother .", rep));
+        let delta = [[":x1", "rdf:type", ":Mother"]];
+        assert!(fire(&graph, &mut SCM_UNI, Some(&delta)).is_empty());
+    }
+
+    #[test]
+    fn malformed_list() {
+        let graph = saturate(
+            ":Laptop owl:intersectionOf :l . :l rdf:first :Computer . :x1 a :Laptop .",
+            vec![Box::new(CLS_INT2)],
+        );
+        assert!(!has(&graph, ":x1", "rdf:type", ":Computer"));
+    }
+}
@@ -0,0 +1,152 @@
+//! The fragments of the ontology languages supported by the reasoner
+
+use crate::rules::*;
+
+/// A fragment of the ontology languages, defining which rules are applied
+///
+/// Each profile includes the rules of the previous ones,
+/// except for `RDFSFull`, which is only included in itself:
+/// `RhoDF` ⊂ `RDFS` ⊂ `RDFSPlus` ⊂ `OWL2RL`, and `RDFS` ⊂ `RDFSFull`.
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+///
+///     :Bart a :human .
+///     :human rdfs:subClassOf :mammal .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::RhoDF);
+/// rules.fire_all(&mut graph);
+/// assert_eq!(graph.size(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// The ρdf fragment: subclasses, subproperties, domains and ranges
+    RhoDF,
+    /// The default RDFS fragment: ρdf plus the propagation of domains and ranges
+    RDFS,
+    /// The full RDFS entailment: RDFS plus the typing of resources, properties,
+    /// classes, datatypes and container membership properties
+    ///
+    /// Unlike the other profiles, it is not included in the following ones,
+    /// as Inferray does for its RDFS-Plus and OWL 2 RL fragments:
+    /// its rules infer triples which hold for every term
+    /// (e.g. `x rdf:type rdfs:Resource`, or `c rdfs:subClassOf c` for every class),
+    /// and would outnumber the useful inferences of these fragments.
+    RDFSFull,
+    /// The RDFS-Plus fragment: RDFS plus equivalences, inverse, transitive,
+    /// symmetric and (inverse) functional properties
+    RDFSPlus,
+    /// The OWL 2 RL fragment, as far as it is supported by this crate
+    OWL2RL,
+}
+
+impl Profile {
+    /// Return the rules of this profile
+    pub fn rules(self) -> Vec<Box<dyn Rule>> {
+        let mut rules: Vec<Box<dyn Rule>> = vec![
+            Box::new(CAX_SCO),
+            Box::new(SCM_SCO),
+            Box::new(SCM_SPO),
+            Box::new(PRP_DOM),
+            Box::new(PRP_RNG),
+            Box::new(PRP_SPO1),
+        ];
+        if self == Profile::RhoDF {
+            return rules;
+        }
+        rules.push(Box::new(SCM_DOM1));
+        rules.push(Box::new(SCM_DOM2));
+        rules.push(Box::new(SCM_RNG1));
+        rules.push(Box::new(SCM_RNG2));
+        if self == Profile::RDFS {
+            return rules;
+        }
+        if self == Profile::RDFSFull {
+            rules.push(Box::new(RDFD2));
+            rules.push(Box::new(RDFS4A));
+            rules.push(Box::new(RDFS4B));
+            rules.push(Box::new(RDFS6));
+            rules.push(Box::new(RDFS8));
+            rules.push(Box::new(RDFS10));
+            rules.push(Box::new(RDFS12));
+            rules.push(Box::new(RDFS13));
+            rules.push(Box::new(RDFS_CMP));
+            return rules;
+        }
+        rules.push(Box::new(CAX_EQC1));
+        rules.push(Box::new(CAX_EQC2));
+        rules.push(Box::new(SCM_EQC2));
+        rules.push(Box::new(SCM_EQP2));
+        rules.push(Box::new(PRP_TRP));
+        rules.push(Box::new(PRP_FP));
+        rules.push(Box::new(PRP_IFP));
+        rules.push(Box::new(PRP_INV_1_2));
+        rules.push(Box::new(PRP_EQP_1_2));
+        rules.push(Box::new(PRP_SYMP));
+        if self == Profile::RDFSPlus {
+            return rules;
+        }
+        rules.push(Box::new(CLS_INT1));
+        rules.push(Box::new(CLS_INT2));
+        rules.push(Box::new(CLS_UNI));
+        rules.push(Box::new(SCM_INT));
+        rules.push(Box::new(SCM_UNI));
+        rules.push(Box::new(CLS_SVF1));
+        rules.push(Box::new(CLS_SVF2));
+        rules.push(Box::new(CLS_AVF));
+        rules.push(Box::new(CLS_HV1));
+        rules.push(Box::new(CLS_HV2));
+        rules.push(Box::new(SCM_SVF1));
+        rules.push(Box::new(SCM_SVF2));
+        rules.push(Box::new(SCM_AVF1));
+        rules.push(Box::new(SCM_AVF2));
+        rules.push(Box::new(SCM_HV));
+        rules.push(Box::new(CLS_MAXC2));
+        rules.push(Box::new(CLS_MAXQC3));
+        rules.push(Box::new(CLS_MAXQC4));
+        rules.push(Box::new(PRP_SPO2));
+        rules
+    }
+
+    /// Return the constraints of this profile, which only RDFS-Plus and OWL 2 RL have
+    pub fn constraints(self) -> Vec<Box<dyn Constraint>> {
+        match self {
+            Profile::RDFSPlus | Profile::OWL2RL => (),
+            _ => return Vec::new(),
+        }
+        let mut constraints: Vec<Box<dyn Constraint>> = vec![Box::new(PRP_FP), Box::new(PRP_IFP)];
+        if self == Profile::RDFSPlus {
+            return constraints;
+        }
+        constraints.extend(vec![
+            Box::new(CAX_DW) as Box<dyn Constraint>,
+            Box::new(CAX_ADC),
+            Box::new(CLS_COM),
+            Box::new(CLS_NOTHING2),
+            Box::new(PRP_IRP),
+            Box::new(PRP_ASYP),
+            Box::new(PRP_PDW),
+            Box::new(PRP_ADP),
+            Box::new(EQ_DIFF1),
+            Box::new(EQ_DIFF2),
+            Box::new(EQ_DIFF3),
+            Box::new(PRP_NPA1),
+            Box::new(PRP_NPA2),
+            Box::new(CLS_MAXC1),
+            Box::new(CLS_MAXQC1),
+            Box::new(CLS_MAXQC2),
+            Box::new(CLS_MAXC2),
+            Box::new(CLS_MAXQC3),
+            Box::new(CLS_MAXQC4),
+        ]);
+        constraints
+    }
+}
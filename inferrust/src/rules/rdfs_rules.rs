@@ -0,0 +1,497 @@
+//! The RDFS class groups the rules of the RDFS entailment
+//! which are not already part of the RDFS ruleset :
+//! <ul>
+//! <li>RDFD2</li>
+//! <li>RDFS4A</li>
+//! <li>RDFS4B</li>
+//! <li>RDFS6</li>
+//! <li>RDFS8</li>
+//! <li>RDFS10</li>
+//! <li>RDFS12</li>
+//! <li>RDFS13</li>
+//! <li>RDFS-CMP</li>
+//! </ul>
+//!
+//! RDFS2, 3, 5, 7, 9 and 11 are PRP-DOM, PRP-RNG, SCM-SPO, PRP-SPO1, CAX-SCO and SCM-SCO.
+//! The axiomatic triples can be added with
+//! [`InfGraph::insert_axiomatic_triples`](../inferray/struct.InfGraph.html#method.insert_axiomatic_triples).
+
+#![allow(non_camel_case_types)]
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::Rule;
+
+/// General method to apply a rule of the RDFS class whose body is `x rdf:type class`
+///
+/// For each instance `x` of `class`, the triple `head(x)` is inferred.
+/// If `only_properties`, the instances which are not properties are ignored.
+fn apply_type_rule<F>(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    class: u64,
+    only_properties: bool,
+    head: F,
+) -> TripleStore
+where
+    F: Fn(u64) -> [u64; 3],
+{
+    let rdftype = graph.dictionary.rdftype as u64;
+    let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+    let mut output = graph.new_output();
+    for pair in delta
+        .unwrap_or(&graph.dictionary.ts)
+        .pairs_with(type_idx, 1, class)
+    {
+        let x = pair[1];
+        if only_properties && !NodeDictionary::is_property(x) {
+            continue;
+        }
+        output.add_inferred_triple(head(x), &[[x, rdftype, class]]);
+    }
+    output
+}
+
+/// The RDFD2 rule from the RDF entailment
+///
+/// Body:
+/// - x p y
+/// Head:
+/// - p rdf:type rdf:Property
+pub struct RDFD2;
+
+impl Rule for RDFD2 {
+    fn name(&self) -> &str {
+        "RDFD2"
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let rdftype = graph.dictionary.rdftype as u64;
+        let property = graph.dictionary.rdfProperty as u64;
+        let mut output = graph.new_output();
+        for (idx, chunk) in delta
+            .unwrap_or(&graph.dictionary.ts)
+            .elem
+            .iter()
+            .enumerate()
+        {
+            if let Some(pair) = chunk[0].first() {
+                let p = NodeDictionary::idx_to_prop_idx(idx);
+                output.add_inferred_triple([p, rdftype, property], &[[pair[0], p, pair[1]]]);
+            }
+        }
+        output
+    }
+}
+
+/// General method to apply RDFS4A (`order == 0`) or RDFS4B (`order == 1`)
+///
+/// Each subject (resp. object) of the store, other than a literal, is typed as `rdfs:Resource`.
+fn apply_resource_rule(graph: &InfGraph, delta: Option<&TripleStore>, order: usize) -> TripleStore {
+    let rdftype = graph.dictionary.rdftype as u64;
+    let resource = graph.dictionary.rdfsResource;
+    let mut output = graph.new_output();
+    for (idx, chunk) in delta
+        .unwrap_or(&graph.dictionary.ts)
+        .elem
+        .iter()
+        .enumerate()
+    {
+        let p = NodeDictionary::idx_to_prop_idx(idx);
+        let mut last = None;
+        for pair in &chunk[order] {
+            let x = pair[0];
+            if last == Some(x) {
+                continue;
+            }
+            last = Some(x);
+            if order == 1 && graph.dictionary.is_literal(x) {
+                continue;
+            }
+            let premise = if order == 0 {
+                [pair[0], p, pair[1]]
+            } else {
+                [pair[1], p, pair[0]]
+            };
+            output.add_inferred_triple([x, rdftype, resource], &[premise]);
+        }
+    }
+    output
+}
+
+/// The RDFS4A rule from the RDFS entailment
+///
+/// Body:
+/// - x p y
+/// Head:
+/// - x rdf:type rdfs:Resource
+pub struct RDFS4A;
+
+impl Rule for RDFS4A {
+    fn name(&self) -> &str {
+        "RDFS4A"
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        apply_resource_rule(graph, delta, 0)
+    }
+}
+
+/// The RDFS4B rule from the RDFS entailment
+///
+/// Body:
+/// - x p y
+/// Head:
+/// - y rdf:type rdfs:Resource
+///
+/// Literals are not typed, as they cannot be the subject of a RDF triple.
+pub struct RDFS4B;
+
+impl Rule for RDFS4B {
+    fn name(&self) -> &str {
+        "RDFS4B"
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        apply_resource_rule(graph, delta, 1)
+    }
+}
+
+/// The RDFS6 rule from the RDFS entailment
+///
+/// Body:
+/// - x rdf:type rdf:Property
+/// Head:
+/// - x rdfs:subPropertyOf x
+pub struct RDFS6;
+
+impl Rule for RDFS6 {
+    fn name(&self) -> &str {
+        "RDFS6"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubPropertyOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let sub_property_of = graph.dictionary.rdfssubPropertyOf as u64;
+        let property = graph.dictionary.rdfProperty as u64;
+        apply_type_rule(graph, delta, property, true, |x| [x, sub_property_of, x])
+    }
+}
+
+/// The RDFS8 rule from the RDFS entailment
+///
+/// Body:
+/// - x rdf:type rdfs:Class
+/// Head:
+/// - x rdfs:subClassOf rdfs:Resource
+pub struct RDFS8;
+
+impl Rule for RDFS8 {
+    fn name(&self) -> &str {
+        "RDFS8"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let sub_class_of = graph.dictionary.rdfssubClassOf as u64;
+        let resource = graph.dictionary.rdfsResource;
+        apply_type_rule(graph, delta, graph.dictionary.rdfsClass, false, |x| {
+            [x, sub_class_of, resource]
+        })
+    }
+}
+
+/// The RDFS10 rule from the RDFS entailment
+///
+/// Body:
+/// - x rdf:type rdfs:Class
+/// Head:
+/// - x rdfs:subClassOf x
+pub struct RDFS10;
+
+impl Rule for RDFS10 {
+    fn name(&self) -> &str {
+        "RDFS10"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let sub_class_of = graph.dictionary.rdfssubClassOf as u64;
+        apply_type_rule(graph, delta, graph.dictionary.rdfsClass, false, |x| {
+            [x, sub_class_of, x]
+        })
+    }
+}
+
+/// The RDFS12 rule from the RDFS entailment
+///
+/// Body:
+/// - x rdf:type rdfs:ContainerMembershipProperty
+/// Head:
+/// - x rdfs:subPropertyOf rdfs:member
+pub struct RDFS12;
+
+impl Rule for RDFS12 {
+    fn name(&self) -> &str {
+        "RDFS12"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubPropertyOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let dictionary = &graph.dictionary;
+        let sub_property_of = dictionary.rdfssubPropertyOf as u64;
+        let member = dictionary.rdfsMember as u64;
+        let class = dictionary.rdfsContainerMembershipProperty as u64;
+        apply_type_rule(graph, delta, class, true, |x| [x, sub_property_of, member])
+    }
+}
+
+/// The RDFS13 rule from the RDFS entailment
+///
+/// Body:
+/// - x rdf:type rdfs:Datatype
+/// Head:
+/// - x rdfs:subClassOf rdfs:Literal
+pub struct RDFS13;
+
+impl Rule for RDFS13 {
+    fn name(&self) -> &str {
+        "RDFS13"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let sub_class_of = graph.dictionary.rdfssubClassOf as u64;
+        let literal = graph.dictionary.rdfsLiteral;
+        apply_type_rule(graph, delta, graph.dictionary.rdfsDatatype, false, |x| {
+            [x, sub_class_of, literal]
+        })
+    }
+}
+
+/// Type the container membership properties used in the store
+///
+/// Body:
+/// - x rdf:_n y
+/// Head:
+/// - rdf:_n rdf:type rdfs:ContainerMembershipProperty
+///
+/// This materializes the axiomatic triples of the properties `rdf:_n` as they get used,
+/// so that RDFS12 makes them sub-properties of `rdfs:member`.
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+/// use sophia::term::RcTerm;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+///
+///     :simpsons a rdf:Bag .
+///     :simpsons rdf:_1 :Bart .
+///     :simpsons rdf:_2 :Lisa .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::RDFSFull);
+/// rules.fire_all(&mut graph);
+///
+/// let index = |iri| {
+///     let term = RcTerm::new_iri(iri).unwrap();
+///     graph.dictionary.get_index(&term).unwrap()
+/// };
+/// let member = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdfsMember as u64);
+/// let simpsons = index("http://example.org/simpsons");
+/// assert!(graph.dictionary.ts.contains_pair(member, [simpsons, index("http://example.org/Bart")]));
+/// assert!(graph.dictionary.ts.contains_pair(member, [simpsons, index("http://example.org/Lisa")]));
+/// ```
+pub struct RDFS_CMP;
+
+impl Rule for RDFS_CMP {
+    fn name(&self) -> &str {
+        "RDFS-CMP"
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let rdftype = graph.dictionary.rdftype as u64;
+        let class = graph.dictionary.rdfsContainerMembershipProperty as u64;
+        let mut output = graph.new_output();
+        for (idx, chunk) in delta
+            .unwrap_or(&graph.dictionary.ts)
+            .elem
+            .iter()
+            .enumerate()
+        {
+            if let Some(pair) = chunk[0].first() {
+                let p = NodeDictionary::idx_to_prop_idx(idx);
+                if graph.dictionary.is_container_membership_property(p) {
+                    output.add_inferred_triple([p, rdftype, class], &[[pair[0], p, pair[1]]]);
+                }
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Profile;
+    use crate::test_utils::*;
+
+    #[test]
+    fn rdfd2_rdfs4() {
+        let rep = r#":Bart :name "Bart" ."#;
+        let graph = saturate(rep, vec![Box::new(RDFD2)]);
+        assert!(has(&graph, ":name", "rdf:type", "rdf:Property"));
+        assert!(!has(&graph, ":Bart", "rdf:type", "rdf:Property"));
+        let graph = saturate(rep, vec![Box::new(RDFS4A), Box::new(RDFS4B)]);
+        assert!(has(&graph, ":Bart", "rdf:type", "rdfs:Resource"));
+        let rdftype = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64);
+        let resource = graph.dictionary.rdfsResource;
+        let typed = graph.dictionary.ts.pairs_with(rdftype, 1, resource);
+        assert!(typed
+            .iter()
+            .all(|pair| !graph.dictionary.is_literal(pair[1])));
+    }
+
+    #[test]
+    fn rdfs6_12() {
+        let graph = saturate(
+            ":name a rdf:Property . rdf:_1 a rdfs:ContainerMembershipProperty . :Bart a :human .",
+            vec![Box::new(RDFS6), Box::new(RDFS12)],
+        );
+        assert!(has(&graph, ":name", "rdfs:subPropertyOf", ":name"));
+        assert!(has(&graph, "rdf:_1", "rdfs:subPropertyOf", "rdfs:member"));
+        assert!(!has(&graph, ":name", "rdfs:subPropertyOf", "rdfs:member"));
+        assert!(!has(&graph, ":Bart", "rdfs:subPropertyOf", ":Bart"));
+    }
+
+    #[test]
+    fn rdfs8_10_13() {
+        let rep = ":human a rdfs:Class . :age a rdfs:Datatype . :Bart a :human .";
+        let graph = saturate(rep, vec![Box::new(RDFS8), Box::new(RDFS10)]);
+        assert!(has(&graph, ":human", "rdfs:subClassOf", "rdfs:Resource"));
+        assert!(has(&graph, ":human", "rdfs:subClassOf", ":human"));
+        assert!(!has(&graph, ":Bart", "rdfs:subClassOf", ":Bart"));
+        let graph = saturate(rep, vec![Box::new(RDFS13)]);
+        assert!(has(&graph, ":age", "rdfs:subClassOf", "rdfs:Literal"));
+        assert!(!has(&graph, ":human", "rdfs:subClassOf", "rdfs:Literal"));
+    }
+
+    #[test]
+    fn rdfs_cmp() {
+        let graph = saturate_profile(
+            ":simpsons a rdf:Bag . :simpsons rdf:_1 :Bart . :simpsons rdf:_2 :Lisa . :simpsons :owner :Fox .",
+            Profile::RDFSFull,
+        );
+        assert!(has(&graph, ":simpsons", "rdfs:member", ":Bart"));
+        assert!(has(&graph, ":simpsons", "rdfs:member", ":Lisa"));
+        assert!(!has(&graph, ":simpsons", "rdfs:member", ":Fox"));
+    }
+
+    #[test]
+    fn rdfd2_delta() {
+        let graph = graph(":Bart :knows :Lisa . :Bart :likes :Milhouse .");
+        let delta = [[":Bart", ":knows", ":Lisa"]];
+        let output = fire(&graph, &mut RDFD2, Some(&delta));
+        assert!(contains(
+            &graph,
+            &output,
+            ":knows",
+            "rdf:type",
+            "rdf:Property"
+        ));
+        assert!(!contains(
+            &graph,
+            &output,
+            ":likes",
+            "rdf:type",
+            "rdf:Property"
+        ));
+    }
+
+    #[test]
+    fn rdfs_cmp_provenance() {
+        let graph = saturate_with_provenance(
+            ":simpsons rdf:_1 :Bart . :simpsons :owner :Fox .",
+            vec![Box::new(RDFS_CMP)],
+        );
+        assert!(has(
+            &graph,
+            "rdf:_1",
+            "rdf:type",
+            "rdfs:ContainerMembershipProperty"
+        ));
+        assert!(!has(
+            &graph,
+            ":owner",
+            "rdf:type",
+            "rdfs:ContainerMembershipProperty"
+        ));
+        // a single triple using the property is kept as premise
+        let derivation = graph
+            .explain(
+                &iri("rdf:_1"),
+                &iri("rdf:type"),
+                &iri("rdfs:ContainerMembershipProperty"),
+            )
+            .unwrap();
+        assert_eq!(derivation.rule, Some("RDFS-CMP"));
+        assert_eq!(derivation.premises.len(), 1);
+    }
+}
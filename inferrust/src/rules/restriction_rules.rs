@@ -0,0 +1,850 @@
+//! The restriction class groups the following rules :
+//! <ul>
+//! <li>CLS-SVF1</li>
+//! <li>CLS-SVF2</li>
+//! <li>CLS-AVF</li>
+//! <li>CLS-HV1</li>
+//! <li>CLS-HV2</li>
+//! <li>SCM-SVF1/2</li>
+//! <li>SCM-AVF1/2</li>
+//! <li>SCM-HV</li>
+//! </ul>
+//!
+//! All these rules use property restrictions `x value_p y`, `x owl:onProperty p`,
+//! where `value_p` is `owl:someValuesFrom`, `owl:allValuesFrom` or `owl:hasValue`.
+//! A `delta` containing a triple of a restriction
+//! triggers a full application of the rule.
+
+#![allow(non_camel_case_types)]
+
+use std::collections::HashMap;
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::Rule;
+use crate::rules::{touches, with_properties};
+
+/// A property restriction `class value_p value`, `class owl:onProperty property`
+struct Restriction {
+    class: u64,
+    property: u64,
+    value: u64,
+}
+
+/// Return the restrictions of property `value_p` found in the store
+///
+/// Restrictions whose property is not used as a predicate are ignored.
+fn restrictions(graph: &InfGraph, value_p: u32) -> Vec<Restriction> {
+    let ts = &graph.dictionary.ts;
+    let on_property = NodeDictionary::prop_idx_to_idx(graph.dictionary.onProperty as u64);
+    let mut restrictions = Vec::new();
+    for pair in ts.pairs(NodeDictionary::prop_idx_to_idx(value_p as u64), 0) {
+        for property_pair in ts.pairs_with(on_property, 0, pair[0]) {
+            if NodeDictionary::is_property(property_pair[1]) {
+                restrictions.push(Restriction {
+                    class: pair[0],
+                    property: property_pair[1],
+                    value: pair[1],
+                });
+            }
+        }
+    }
+    restrictions
+}
+
+/// Return the triples of `restriction`, as premises of an inferred triple
+fn restriction_premises(
+    graph: &InfGraph,
+    value_p: u32,
+    restriction: &Restriction,
+) -> Vec<[u64; 3]> {
+    vec![
+        [restriction.class, value_p as u64, restriction.value],
+        [
+            restriction.class,
+            graph.dictionary.onProperty as u64,
+            restriction.property,
+        ],
+    ]
+}
+
+/// Return the predicates read by a rule on the restrictions of property `value_p`
+fn restriction_inputs(dictionary: &NodeDictionary, value_p: u32, with_type: bool) -> Vec<u32> {
+    let on_property = NodeDictionary::prop_idx_to_idx(dictionary.onProperty as u64);
+    let mut predicates = vec![value_p, dictionary.onProperty];
+    if with_type {
+        predicates.push(dictionary.rdftype);
+    }
+    with_properties(
+        predicates,
+        dictionary
+            .ts
+            .pairs(on_property, 0)
+            .iter()
+            .map(|pair| pair[1]),
+    )
+}
+
+/// Return `true` if `delta` is `None`, or contains triples of a restriction of property `value_p`
+fn schema_changed(graph: &InfGraph, delta: Option<&TripleStore>, value_p: u32) -> bool {
+    touches(delta, &[value_p, graph.dictionary.onProperty])
+}
+
+/// The CLS-SVF1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:someValuesFrom y
+/// - x owl:onProperty p
+/// - u p v
+/// - v rdf:type y
+/// Head:
+/// - u rdf:type x
+///
+/// # Example
+///
+/// ```
+/// use inferrust::inferray::*;
+/// use inferrust::rules::*;
+/// use sophia::term::RcTerm;
+///
+/// let rep = r#"
+///     @prefix : <http://example.org/> .
+///     @prefix owl: <http://www.w3.org/2002/07/owl#> .
+///
+///     :Parent owl:someValuesFrom :human .
+///     :Parent owl:onProperty :hasChild .
+///     :Homer :hasChild :Bart .
+///     :Bart a :human .
+/// "#;
+/// let mut graph = InfGraph::from(sophia::parser::turtle::parse_str(rep));
+/// let mut rules = <Vec<Box<dyn Rule>> as RuleSet>::with_profile(Profile::OWL2RL);
+/// rules.fire_all(&mut graph);
+///
+/// let index = |iri| {
+///     let term = RcTerm::new_iri(iri).unwrap();
+///     graph.dictionary.get_index(&term).unwrap()
+/// };
+/// let rdftype = NodeDictionary::prop_idx_to_idx(graph.dictionary.rdftype as u64);
+/// assert!(graph.dictionary.ts.contains_pair(
+///     rdftype,
+///     [index("http://example.org/Homer"), index("http://example.org/Parent")]
+/// ));
+/// ```
+pub struct CLS_SVF1;
+
+impl Rule for CLS_SVF1 {
+    fn name(&self) -> &str {
+        "CLS-SVF1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(restriction_inputs(
+            dictionary,
+            dictionary.owlsomeValuesFrom,
+            true,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let ts = &graph.dictionary.ts;
+        let value_p = graph.dictionary.owlsomeValuesFrom;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+        let full = schema_changed(graph, delta, value_p);
+        let source = if full {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for restriction in restrictions(graph, value_p) {
+            let (x, p, y) = (restriction.class, restriction.property, restriction.value);
+            let p_idx = NodeDictionary::prop_idx_to_idx(p);
+            let mut infer = |u: u64, v: u64| {
+                let mut premises = restriction_premises(graph, value_p, &restriction);
+                premises.push([u, p, v]);
+                premises.push([v, rdftype, y]);
+                output.add_inferred_triple([u, rdftype, x], &premises);
+            };
+            // new `u p v` triples
+            for pair in source.pairs(p_idx, 0) {
+                if ts.contains_pair(type_idx, [pair[1], y]) {
+                    infer(pair[0], pair[1]);
+                }
+            }
+            // new `v rdf:type y` triples
+            if !full {
+                for type_pair in source.pairs_with(type_idx, 1, y) {
+                    for pair in ts.pairs_with(p_idx, 1, type_pair[1]) {
+                        infer(pair[1], pair[0]);
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+/// The CLS-SVF2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:someValuesFrom owl:Thing
+/// - x owl:onProperty p
+/// - u p v
+/// Head:
+/// - u rdf:type x
+pub struct CLS_SVF2;
+
+impl Rule for CLS_SVF2 {
+    fn name(&self) -> &str {
+        "CLS-SVF2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(restriction_inputs(
+            dictionary,
+            dictionary.owlsomeValuesFrom,
+            false,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let value_p = graph.dictionary.owlsomeValuesFrom;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let thing = graph.dictionary.owlthing as u64;
+        let full = schema_changed(graph, delta, value_p);
+        let source = if full {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for restriction in restrictions(graph, value_p) {
+            if restriction.value != thing {
+                continue;
+            }
+            let (x, p) = (restriction.class, restriction.property);
+            for pair in source.pairs(NodeDictionary::prop_idx_to_idx(p), 0) {
+                let mut premises = restriction_premises(graph, value_p, &restriction);
+                premises.push([pair[0], p, pair[1]]);
+                output.add_inferred_triple([pair[0], rdftype, x], &premises);
+            }
+        }
+        output
+    }
+}
+
+/// The CLS-AVF rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:allValuesFrom y
+/// - x owl:onProperty p
+/// - u rdf:type x
+/// - u p v
+/// Head:
+/// - v rdf:type y
+pub struct CLS_AVF;
+
+impl Rule for CLS_AVF {
+    fn name(&self) -> &str {
+        "CLS-AVF"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(restriction_inputs(
+            dictionary,
+            dictionary.owlallValuesFrom,
+            true,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let ts = &graph.dictionary.ts;
+        let value_p = graph.dictionary.owlallValuesFrom;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+        let full = schema_changed(graph, delta, value_p);
+        let source = if full {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for restriction in restrictions(graph, value_p) {
+            let (x, p, y) = (restriction.class, restriction.property, restriction.value);
+            let p_idx = NodeDictionary::prop_idx_to_idx(p);
+            let mut infer = |u: u64, v: u64| {
+                let mut premises = restriction_premises(graph, value_p, &restriction);
+                premises.push([u, rdftype, x]);
+                premises.push([u, p, v]);
+                output.add_inferred_triple([v, rdftype, y], &premises);
+            };
+            // new `u rdf:type x` triples
+            for type_pair in source.pairs_with(type_idx, 1, x) {
+                for pair in ts.pairs_with(p_idx, 0, type_pair[1]) {
+                    infer(pair[0], pair[1]);
+                }
+            }
+            // new `u p v` triples
+            if !full {
+                for pair in source.pairs(p_idx, 0) {
+                    if ts.contains_pair(type_idx, [pair[0], x]) {
+                        infer(pair[0], pair[1]);
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+/// The CLS-HV1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:hasValue y
+/// - x owl:onProperty p
+/// - u rdf:type x
+/// Head:
+/// - u p y
+pub struct CLS_HV1;
+
+impl Rule for CLS_HV1 {
+    fn name(&self) -> &str {
+        "CLS-HV1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![
+            dictionary.owlhasValue,
+            dictionary.onProperty,
+            dictionary.rdftype,
+        ])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        let on_property = NodeDictionary::prop_idx_to_idx(dictionary.onProperty as u64);
+        Some(with_properties(
+            vec![],
+            dictionary
+                .ts
+                .pairs(on_property, 0)
+                .iter()
+                .map(|pair| pair[1]),
+        ))
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let value_p = graph.dictionary.owlhasValue;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let type_idx = NodeDictionary::prop_idx_to_idx(rdftype);
+        let full = schema_changed(graph, delta, value_p);
+        let source = if full {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for restriction in restrictions(graph, value_p) {
+            let (x, p, y) = (restriction.class, restriction.property, restriction.value);
+            for type_pair in source.pairs_with(type_idx, 1, x) {
+                let mut premises = restriction_premises(graph, value_p, &restriction);
+                premises.push([type_pair[1], rdftype, x]);
+                output.add_inferred_triple([type_pair[1], p, y], &premises);
+            }
+        }
+        output
+    }
+}
+
+/// The CLS-HV2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - x owl:hasValue y
+/// - x owl:onProperty p
+/// - u p y
+/// Head:
+/// - u rdf:type x
+pub struct CLS_HV2;
+
+impl Rule for CLS_HV2 {
+    fn name(&self) -> &str {
+        "CLS-HV2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(restriction_inputs(
+            dictionary,
+            dictionary.owlhasValue,
+            false,
+        ))
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdftype])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let value_p = graph.dictionary.owlhasValue;
+        let rdftype = graph.dictionary.rdftype as u64;
+        let full = schema_changed(graph, delta, value_p);
+        let source = if full {
+            &graph.dictionary.ts
+        } else {
+            delta.unwrap()
+        };
+        let mut output = graph.new_output();
+        for restriction in restrictions(graph, value_p) {
+            let (x, p, y) = (restriction.class, restriction.property, restriction.value);
+            for pair in source.pairs_with(NodeDictionary::prop_idx_to_idx(p), 1, y) {
+                let mut premises = restriction_premises(graph, value_p, &restriction);
+                premises.push([pair[1], p, y]);
+                output.add_inferred_triple([pair[1], rdftype, x], &premises);
+            }
+        }
+        output
+    }
+}
+
+/// General method to apply a schema rule of the restriction class
+///
+/// The restrictions of property `value_p` sharing the same property (if `link_values`)
+/// or the same value (otherwise) are compared pairwise.
+/// For each pair of restrictions `c1`, `c2` whose values (if `link_values`)
+/// or properties (otherwise) are equal or linked by `link_p`,
+/// the triple `head(c1, c2)` is inferred.
+fn apply_restriction_schema_rule<F>(
+    graph: &InfGraph,
+    delta: Option<&TripleStore>,
+    value_p: u32,
+    link_p: u32,
+    link_values: bool,
+    head: F,
+) -> TripleStore
+where
+    F: Fn(u64, u64) -> [u64; 3],
+{
+    let ts = &graph.dictionary.ts;
+    let mut output = graph.new_output();
+    if !touches(delta, &[value_p, graph.dictionary.onProperty, link_p]) {
+        return output;
+    }
+    let link_idx = NodeDictionary::prop_idx_to_idx(link_p as u64);
+    let mut groups: HashMap<u64, Vec<Restriction>> = HashMap::new();
+    for restriction in restrictions(graph, value_p) {
+        let key = if link_values {
+            restriction.property
+        } else {
+            restriction.value
+        };
+        groups.entry(key).or_default().push(restriction);
+    }
+    for group in groups.values() {
+        for r1 in group {
+            for r2 in group {
+                if r1.class == r2.class {
+                    continue;
+                }
+                let (l1, l2) = if link_values {
+                    (r1.value, r2.value)
+                } else {
+                    (r1.property, r2.property)
+                };
+                if l1 != l2 && !ts.contains_pair(link_idx, [l1, l2]) {
+                    continue;
+                }
+                let mut premises = restriction_premises(graph, value_p, r1);
+                premises.extend(restriction_premises(graph, value_p, r2));
+                if l1 != l2 {
+                    premises.push([l1, link_p as u64, l2]);
+                }
+                output.add_inferred_triple(head(r1.class, r2.class), &premises);
+            }
+        }
+    }
+    output
+}
+
+/// The SCM-SVF1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:someValuesFrom y1
+/// - c1 owl:onProperty p
+/// - c2 owl:someValuesFrom y2
+/// - c2 owl:onProperty p
+/// - y1 rdfs:subClassOf y2
+/// Head:
+/// - c1 rdfs:subClassOf c2
+pub struct SCM_SVF1;
+
+impl Rule for SCM_SVF1 {
+    fn name(&self) -> &str {
+        "SCM-SVF1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![
+            dictionary.owlsomeValuesFrom,
+            dictionary.onProperty,
+            dictionary.rdfssubClassOf,
+        ])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let dictionary = &graph.dictionary;
+        let sub_class_of = dictionary.rdfssubClassOf as u64;
+        apply_restriction_schema_rule(
+            graph,
+            delta,
+            dictionary.owlsomeValuesFrom,
+            dictionary.rdfssubClassOf,
+            true,
+            |c1, c2| [c1, sub_class_of, c2],
+        )
+    }
+}
+
+/// The SCM-SVF2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:someValuesFrom y
+/// - c1 owl:onProperty p1
+/// - c2 owl:someValuesFrom y
+/// - c2 owl:onProperty p2
+/// - p1 rdfs:subPropertyOf p2
+/// Head:
+/// - c1 rdfs:subClassOf c2
+pub struct SCM_SVF2;
+
+impl Rule for SCM_SVF2 {
+    fn name(&self) -> &str {
+        "SCM-SVF2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![
+            dictionary.owlsomeValuesFrom,
+            dictionary.onProperty,
+            dictionary.rdfssubPropertyOf,
+        ])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let dictionary = &graph.dictionary;
+        let sub_class_of = dictionary.rdfssubClassOf as u64;
+        apply_restriction_schema_rule(
+            graph,
+            delta,
+            dictionary.owlsomeValuesFrom,
+            dictionary.rdfssubPropertyOf,
+            false,
+            |c1, c2| [c1, sub_class_of, c2],
+        )
+    }
+}
+
+/// The SCM-AVF1 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:allValuesFrom y1
+/// - c1 owl:onProperty p
+/// - c2 owl:allValuesFrom y2
+/// - c2 owl:onProperty p
+/// - y1 rdfs:subClassOf y2
+/// Head:
+/// - c1 rdfs:subClassOf c2
+pub struct SCM_AVF1;
+
+impl Rule for SCM_AVF1 {
+    fn name(&self) -> &str {
+        "SCM-AVF1"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![
+            dictionary.owlallValuesFrom,
+            dictionary.onProperty,
+            dictionary.rdfssubClassOf,
+        ])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let dictionary = &graph.dictionary;
+        let sub_class_of = dictionary.rdfssubClassOf as u64;
+        apply_restriction_schema_rule(
+            graph,
+            delta,
+            dictionary.owlallValuesFrom,
+            dictionary.rdfssubClassOf,
+            true,
+            |c1, c2| [c1, sub_class_of, c2],
+        )
+    }
+}
+
+/// The SCM-AVF2 rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:allValuesFrom y
+/// - c1 owl:onProperty p1
+/// - c2 owl:allValuesFrom y
+/// - c2 owl:onProperty p2
+/// - p1 rdfs:subPropertyOf p2
+/// Head:
+/// - c2 rdfs:subClassOf c1
+pub struct SCM_AVF2;
+
+impl Rule for SCM_AVF2 {
+    fn name(&self) -> &str {
+        "SCM-AVF2"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![
+            dictionary.owlallValuesFrom,
+            dictionary.onProperty,
+            dictionary.rdfssubPropertyOf,
+        ])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let dictionary = &graph.dictionary;
+        let sub_class_of = dictionary.rdfssubClassOf as u64;
+        apply_restriction_schema_rule(
+            graph,
+            delta,
+            dictionary.owlallValuesFrom,
+            dictionary.rdfssubPropertyOf,
+            false,
+            |c1, c2| [c2, sub_class_of, c1],
+        )
+    }
+}
+
+/// The SCM-HV rule from the OWL 2 RL ruleset
+///
+/// Body:
+/// - c1 owl:hasValue i
+/// - c1 owl:onProperty p1
+/// - c2 owl:hasValue i
+/// - c2 owl:onProperty p2
+/// - p1 rdfs:subPropertyOf p2
+/// Head:
+/// - c1 rdfs:subClassOf c2
+pub struct SCM_HV;
+
+impl Rule for SCM_HV {
+    fn name(&self) -> &str {
+        "SCM-HV"
+    }
+
+    fn input_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![
+            dictionary.owlhasValue,
+            dictionary.onProperty,
+            dictionary.rdfssubPropertyOf,
+        ])
+    }
+
+    fn output_predicates(&self, dictionary: &NodeDictionary) -> Option<Vec<u32>> {
+        Some(vec![dictionary.rdfssubClassOf])
+    }
+
+    fn fire(&mut self, graph: &InfGraph, delta: Option<&TripleStore>) -> TripleStore {
+        let dictionary = &graph.dictionary;
+        let sub_class_of = dictionary.rdfssubClassOf as u64;
+        apply_restriction_schema_rule(
+            graph,
+            delta,
+            dictionary.owlhasValue,
+            dictionary.rdfssubPropertyOf,
+            false,
+            |c1, c2| [c1, sub_class_of, c2],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn cls_svf() {
+        let graph = saturate(
+            r#"
+            :Parent owl:someValuesFrom :human .
+            :Parent owl:onProperty :hasChild .
+            :Homer :hasChild :Bart . :Bart a :human .
+            :Ned :hasChild :Rod .
+            "#,
+            vec![Box::new(CLS_SVF1)],
+        );
+        assert!(has(&graph, ":Homer", "rdf:type", ":Parent"));
+        assert!(!has(&graph, ":Ned", "rdf:type", ":Parent"));
+        let graph = saturate(
+            r#"
+            :Parent owl:someValuesFrom owl:Thing .
+            :Parent owl:onProperty :hasChild .
+            :Ned :hasChild :Rod .
+            :Ned :knows :Homer .
+            :Homer :knows :Ned .
+            "#,
+            vec![Box::new(CLS_SVF2)],
+        );
+        assert!(has(&graph, ":Ned", "rdf:type", ":Parent"));
+        assert!(!has(&graph, ":Homer", "rdf:type", ":Parent"));
+    }
+
+    #[test]
+    fn cls_svf1_delta() {
+        let graph = graph(
+            r#"
+            :Parent owl:someValuesFrom :human .
+            :Parent owl:onProperty :hasChild .
+            :Homer :hasChild :Bart . :Bart a :human .
+            :Ned :hasChild :Rod .
+            "#,
+        );
+        // a new type of the value joins the existing pairs
+        let delta = [[":Bart", "rdf:type", ":human"]];
+        let output = fire(&graph, &mut CLS_SVF1, Some(&delta));
+        assert!(contains(&graph, &output, ":Homer", "rdf:type", ":Parent"));
+        let delta = [[":Ned", ":hasChild", ":Rod"]];
+        assert!(fire(&graph, &mut CLS_SVF1, Some(&delta)).is_empty());
+    }
+
+    #[test]
+    fn cls_avf() {
+        let graph = saturate_with_provenance(
+            r#"
+            :HumanParent owl:allValuesFrom :human .
+            :HumanParent owl:onProperty :hasChild .
+            :Homer a :HumanParent . :Homer :hasChild :Bart .
+            :Ned :hasChild :Rod .
+            "#,
+            vec![Box::new(CLS_AVF)],
+        );
+        assert!(has(&graph, ":Bart", "rdf:type", ":human"));
+        assert!(!has(&graph, ":Rod", "rdf:type", ":human"));
+        // the restriction, the type of the subject and the pair
+        let derivation = graph
+            .explain(&iri(":Bart"), &iri("rdf:type"), &iri(":human"))
+            .unwrap();
+        assert_eq!(derivation.rule, Some("CLS-AVF"));
+        assert_eq!(derivation.premises.len(), 4);
+    }
+
+    #[test]
+    fn cls_hv() {
+        let rep = r#"
+            :Simpson owl:hasValue :Springfield .
+            :Simpson owl:onProperty :livesIn .
+            :Homer a :Simpson .
+            :Ned :livesIn :Springfield .
+            :Hank :livesIn :CypressCreek .
+        "#;
+        let graph = saturate(rep, vec![Box::new(CLS_HV1)]);
+        assert!(has(&graph, ":Homer", ":livesIn", ":Springfield"));
+        assert!(!has(&graph, ":Ned", "rdf:type", ":Simpson"));
+        let graph = saturate(rep, vec![Box::new(CLS_HV2)]);
+        assert!(has(&graph, ":Ned", "rdf:type", ":Simpson"));
+        assert!(!has(&graph, ":Hank", "rdf:type", ":Simpson"));
+    }
+
+    #[test]
+    fn scm_svf() {
+        let graph = saturate(
+            r#"
+            :c1 owl:someValuesFrom :human . :c1 owl:onProperty :hasChild .
+            :c2 owl:someValuesFrom :mammal . :c2 owl:onProperty :hasChild .
+            :c3 owl:someValuesFrom :robot . :c3 owl:onProperty :hasChild .
+            :human rdfs:subClassOf :mammal .
+            "#,
+            vec![Box::new(SCM_SVF1)],
+        );
+        assert!(has(&graph, ":c1", "rdfs:subClassOf", ":c2"));
+        assert!(!has(&graph, ":c3", "rdfs:subClassOf", ":c2"));
+        let graph = saturate(
+            r#"
+            :c1 owl:someValuesFrom :human . :c1 owl:onProperty :hasSon .
+            :c2 owl:someValuesFrom :human . :c2 owl:onProperty :hasChild .
+            :c3 owl:someValuesFrom :human . :c3 owl:onProperty :knows .
+            :hasSon rdfs:subPropertyOf :hasChild .
+            "#,
+            vec![Box::new(SCM_SVF2)],
+        );
+        assert!(has(&graph, ":c1", "rdfs:subClassOf", ":c2"));
+        assert!(!has(&graph, ":c3", "rdfs:subClassOf", ":c2"));
+    }
+
+    #[test]
+    fn scm_avf() {
+        let graph = saturate(
+            r#"
+            :c1 owl:allValuesFrom :human . :c1 owl:onProperty :hasChild .
+            :c2 owl:allValuesFrom :mammal . :c2 owl:onProperty :hasChild .
+            :c3 owl:allValuesFrom :robot . :c3 owl:onProperty :hasChild .
+            :human rdfs:subClassOf :mammal .
+            "#,
+            vec![Box::new(SCM_AVF1)],
+        );
+        assert!(has(&graph, ":c1", "rdfs:subClassOf", ":c2"));
+        assert!(!has(&graph, ":c3", "rdfs:subClassOf", ":c2"));
+        let graph = saturate(
+            r#"
+            :c1 owl:allValuesFrom :human . :c1 owl:onProperty :hasSon .
+            :c2 owl:allValuesFrom :human . :c2 owl:onProperty :hasChild .
+            :c3 owl:allValuesFrom :human . :c3 owl:onProperty :knows .
+            :hasSon rdfs:subPropertyOf :hasChild .
+            "#,
+            vec![Box::new(SCM_AVF2)],
+        );
+        assert!(has(&graph, ":c2", "rdfs:subClassOf", ":c1"));
+        assert!(!has(&graph, ":c1", "rdfs:subClassOf", ":c2"));
+        assert!(!has(&graph, ":c3", "rdfs:subClassOf", ":c1"));
+    }
+
+    #[test]
+    fn scm_hv() {
+        let graph = saturate(
+            r#"
+            :c1 owl:hasValue :Springfield . :c1 owl:onProperty :livesIn .
+            :c2 owl:hasValue :Springfield . :c2 owl:onProperty :locatedIn .
+            :c3 owl:hasValue :Shelbyville . :c3 owl:onProperty :locatedIn .
+            :livesIn rdfs:subPropertyOf :locatedIn .
+            "#,
+            vec![Box::new(SCM_HV)],
+        );
+        assert!(has(&graph, ":c1", "rdfs:subClassOf", ":c2"));
+        assert!(!has(&graph, ":c1", "rdfs:subClassOf", ":c3"));
+    }
+}
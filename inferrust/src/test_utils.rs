@@ -0,0 +1,138 @@
+//! Helpers shared by the unit tests of the crate
+
+use sophia::term::RcTerm;
+
+use std::collections::BTreeSet;
+
+use crate::inferray::InfGraph;
+use crate::inferray::NodeDictionary;
+use crate::inferray::TripleStore;
+use crate::rules::{Profile, Rule, RuleSet};
+
+/// The prefixes which can be used by the documents given to [`graph`](fn.graph.html)
+const PREFIXES: &str = r#"
+    @prefix : <http://example.org/> .
+    @prefix rdf: <http://www.w3.org/1999/02/22-rdf-syntax-ns#> .
+    @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+    @prefix owl: <http://www.w3.org/2002/07/owl#> .
+    @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+"#;
+
+/// Parse the turtle document `rep`, which may use the prefixes `:`, `rdf:`, `rdfs:`, `owl:` and `xsd:`
+pub fn graph(rep: &str) -> InfGraph {
+    let rep = format!("{}{}", PREFIXES, rep);
+    InfGraph::from(sophia::parser::turtle::parse_str(&rep))
+}
+
+/// Parse the turtle document `rep`, and remove its triples from `graph` with `rules`
+pub fn delete(graph: &mut InfGraph, rep: &str, rules: &mut Vec<Box<dyn Rule>>) {
+    let rep = format!("{}{}", PREFIXES, rep);
+    graph.delete_and_reason(sophia::parser::turtle::parse_str(&rep), rules);
+}
+
+/// Parse the turtle document `rep`, and saturate it with `rules`
+pub fn saturate(rep: &str, mut rules: Vec<Box<dyn Rule>>) -> InfGraph {
+    let mut graph = graph(rep);
+    rules.fire_all(&mut graph);
+    graph
+}
+
+/// Parse the turtle document `rep`, and saturate it with `rules`, recording the provenance
+pub fn saturate_with_provenance(rep: &str, mut rules: Vec<Box<dyn Rule>>) -> InfGraph {
+    let mut graph = graph(rep);
+    graph.enable_provenance();
+    rules.fire_all(&mut graph);
+    graph
+}
+
+/// Parse the turtle document `rep`, and saturate it with the rules of `profile`
+pub fn saturate_profile(rep: &str, profile: Profile) -> InfGraph {
+    saturate(rep, profile.rules())
+}
+
+/// Return the IRI of the prefixed name `name`
+pub fn iri(name: &str) -> RcTerm {
+    let colon = name.find(':').expect("not a prefixed name");
+    let namespace = match &name[..colon] {
+        "" => "http://example.org/",
+        "rdf" => "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+        "rdfs" => "http://www.w3.org/2000/01/rdf-schema#",
+        "owl" => "http://www.w3.org/2002/07/owl#",
+        "xsd" => "http://www.w3.org/2001/XMLSchema#",
+        prefix => panic!("unknown prefix {}", prefix),
+    };
+    RcTerm::new_iri(format!("{}{}", namespace, &name[colon + 1..])).unwrap()
+}
+
+/// Return the index of the prefixed name `name` in `graph`, if it is known
+pub fn index(graph: &InfGraph, name: &str) -> Option<u64> {
+    graph.dictionary.get_index(&iri(name))
+}
+
+/// Return `true` if `graph` contains the triple `s p o`, given as prefixed names
+pub fn has(graph: &InfGraph, s: &str, p: &str, o: &str) -> bool {
+    contains(graph, &graph.dictionary.ts, s, p, o)
+}
+
+/// Return `true` if the sorted `store` contains the triple `s p o`,
+/// given as prefixed names known by `graph`
+pub fn contains(graph: &InfGraph, store: &TripleStore, s: &str, p: &str, o: &str) -> bool {
+    match (index(graph, s), index(graph, p), index(graph, o)) {
+        (Some(s), Some(p), Some(o)) if NodeDictionary::is_property(p) => {
+            store.contains_pair(NodeDictionary::prop_idx_to_idx(p), [s, o])
+        }
+        _ => false,
+    }
+}
+
+/// Return the sorted store of `triples`, given as prefixed names known by `graph`,
+/// e.g. to be used as the `delta` of a rule
+pub fn store(graph: &InfGraph, triples: &[[&str; 3]]) -> TripleStore {
+    let mut store = TripleStore::new();
+    for [s, p, o] in triples {
+        let index = |name| index(graph, name).expect("unknown term");
+        store.add_triple([index(s), index(p), index(o)]);
+    }
+    store.sort();
+    store
+}
+
+/// Fire `rule` once on `graph`, with the triples of `delta` if any, and return its sorted output
+pub fn fire(graph: &InfGraph, rule: &mut dyn Rule, delta: Option<&[[&str; 3]]>) -> TripleStore {
+    let delta = delta.map(|triples| store(graph, triples));
+    let mut output = rule.fire(graph, delta.as_ref());
+    output.sort();
+    output
+}
+
+/// Return the name of the rule which inferred the triple `s p o`, given as prefixed names,
+/// if `graph` records the provenance
+pub fn inferred_by<'a>(graph: &'a InfGraph, s: &str, p: &str, o: &str) -> Option<&'a str> {
+    graph.explain(&iri(s), &iri(p), &iri(o))?.rule
+}
+
+/// Return the triples of `graph`, written with every member of the `owl:sameAs` clique of their terms
+///
+/// Unlike the indexes of the store, they do not depend on the history of the graph.
+pub fn entailed(graph: &InfGraph) -> BTreeSet<String> {
+    let dictionary = &graph.dictionary;
+    let mut entailed = BTreeSet::new();
+    for (idx, chunk) in dictionary.ts.elem.iter().enumerate() {
+        let ip = NodeDictionary::idx_to_prop_idx(idx);
+        for pair in &chunk[0] {
+            for s in dictionary.equivalents(pair[0]) {
+                for p in dictionary.equivalents(ip) {
+                    for o in dictionary.equivalents(pair[1]) {
+                        entailed.insert(format!(
+                            "{:?} {:?} {:?}",
+                            dictionary.get_term(s),
+                            dictionary.get_term(p),
+                            dictionary.get_term(o)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    entailed
+}